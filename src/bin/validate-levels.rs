@@ -0,0 +1,28 @@
+use std::path::Path;
+use std::process::ExitCode;
+use ages_discovery::modules::level::validation::{find_projects, validate_project};
+
+fn main() -> ExitCode {
+    let assets_directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let projects = find_projects(&assets_directory.join("tilemaps"));
+    if projects.is_empty() {
+        println!("No .ldtk files found under {}", assets_directory.join("tilemaps").display());
+        return ExitCode::FAILURE;
+    }
+
+    let mut failed = false;
+    for project in projects.iter() {
+        let errors = validate_project(project, &assets_directory);
+        if errors.is_empty() {
+            println!("ok    {}", project.display());
+        } else {
+            failed = true;
+            println!("FAIL  {}", project.display());
+            for error in errors {
+                println!("      - {}", error);
+            }
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -1,24 +1,60 @@
-mod modules;
-
 use bevy::app::{App, PluginGroup};
 use bevy::DefaultPlugins;
-use bevy::prelude::{Commands, ImagePlugin, IntoSystemDescriptor, Msaa};
-use bevy::time::Timer;
+use bevy::prelude::{CoreStage, ImagePlugin, IntoSystemDescriptor, Msaa, StartupStage};
 use bevy::utils::default;
-use bevy_ecs_ldtk::{LdtkPlugin, LevelSelection};
+use bevy_ecs_ldtk::{LdtkPlugin, LdtkSystemLabel, LevelSelection};
 use bevy_ecs_ldtk::app::RegisterLdtkObjects;
-use bevy_kira_audio::AudioPlugin;
+use bevy_ecs_ldtk::prelude::{LdtkSettings, LevelSpawnBehavior};
+use bevy_kira_audio::{AudioApp, AudioPlugin};
 use bevy_pixel_camera::PixelCameraPlugin;
-use crate::modules::camera::systems::camera_follow;
-use crate::modules::init::systems::{DebugPlugin, setup_game};
-use crate::modules::input::systems::read_input;
-use crate::modules::living::components::PlayerBundle;
-use crate::modules::physics::sprite_change::systems::{overlap_sprite_zones, sync_self_physical_multiple_movement, update_movement_sided_sprite, update_sided_sprite};
-use crate::modules::physics::systems::{collider_direction_react, direction_react, update_movement_state_by_direction, update_sideds_by_direction};
-use crate::modules::prop::components::{RockBundle, TreeStumpBundle};
-use crate::modules::rng::components::RngResource;
-use crate::modules::sound::components::SoundEvent;
-use crate::modules::sound::system::on_sound_emit;
+use ages_discovery::modules::ai::components::BehaviourTrees;
+use ages_discovery::modules::animal::components::AnimalRegistry;
+use ages_discovery::modules::animal::systems::{spawn_level_animals, spawn_placed_animals, update_animal_activity, update_animals};
+use ages_discovery::modules::ai::systems::{attach_brains, tick_brains, update_perception};
+use ages_discovery::modules::building::components::{BuildMode, PlacedStructures, StructurePlaced};
+use ages_discovery::modules::building::systems::{load_structures, place_structure, spawn_level_structures, toggle_build_mode, update_build_ghost, update_build_menu, update_build_placement};
+use ages_discovery::modules::camera::systems::camera_follow;
+use ages_discovery::modules::clock::components::{ClockEvent, Daylight, WorldClock};
+use ages_discovery::modules::clock::systems::{advance_clock, restore_sprite_colors, tint_sprites, tint_tiles, update_daylight};
+use ages_discovery::modules::crafting::components::{Crafted, CraftingMenu, RecipeUnlocked};
+use ages_discovery::modules::crafting::systems::{load_recipes, queue_recipes, spawn_placed_workstations, toggle_crafting_menu, unlock_recipes, update_crafting_menu, update_crafting_queues, update_workstation_sprites};
+use ages_discovery::modules::hot_reload::components::HotReloadState;
+use ages_discovery::modules::hot_reload::systems::{detach_player_on_reload, reattach_player_after_reload};
+use ages_discovery::modules::init::systems::{DebugPlugin, setup_game};
+use ages_discovery::modules::input::components::OpenMenus;
+use ages_discovery::modules::input::systems::read_input;
+use ages_discovery::modules::gathering::components::{GatherEvent, GatheringNodes};
+use ages_discovery::modules::gathering::systems::{attach_harvestables, gather, regrow_harvestables, update_gathering, update_harvestable_prompts, update_harvestable_sprites};
+use ages_discovery::modules::inventory::components::{InventoryChanged, ItemDatabase};
+use ages_discovery::modules::inventory::systems::{drop_gathered_items, drop_loot, pick_up_items, update_carried_tools};
+use ages_discovery::modules::interaction::components::{Interacted, InteractionFocus};
+use ages_discovery::modules::interaction::systems::{read_interact_input, update_interact_prompt, update_interaction_focus};
+use ages_discovery::modules::level::components::{LastCheckpoint, LevelTransition};
+use ages_discovery::modules::level::consts::START_LEVEL_IDENTIFIER;
+use ages_discovery::modules::level::entities::RegisterLevelEntities;
+use ages_discovery::modules::level::systems::{despawn_duplicate_players, enter_doors, reach_checkpoints, select_level_by_player_position, update_level_transition};
+use ages_discovery::modules::living::components::{DamageEvent, DeathEvent};
+use ages_discovery::modules::living::systems::{apply_damage, attack, handle_deaths, update_attacking, update_hurt, update_stamina, update_stamina_bar};
+use ages_discovery::modules::music::components::{MusicController, MusicDucking};
+use ages_discovery::modules::music::systems::{duck_music, update_music};
+use ages_discovery::modules::navigation::components::{NavGrid, PathCache, PathRequests};
+use ages_discovery::modules::navigation::consts::COLLISION_INT_GRID_VALUE;
+use ages_discovery::modules::navigation::systems::{process_path_requests, update_nav_grid};
+use ages_discovery::modules::npc::systems::update_behaviours;
+use ages_discovery::modules::physics::components::BlockedCellBundle;
+use ages_discovery::modules::physics::sprite_change::systems::{overlap_sprite_zones, sync_self_physical_multiple_movement, update_movement_sided_sprite, update_sided_sprite};
+use ages_discovery::modules::physics::systems::{collider_direction_react, direction_react, update_movement_state_by_direction, update_sideds_by_direction};
+use ages_discovery::modules::rng::components::RngResource;
+use ages_discovery::modules::save::components::{ActiveSlot, LoadEvent, Playtime, SaveEvent};
+use ages_discovery::modules::save::systems::{load_game, read_save_input, save_game, tick_playtime};
+use ages_discovery::modules::sound::components::{AmbienceChannel, AudioSettings, MusicChannel, PlayingSounds, SfxChannel, SoundEvent, UiChannel};
+use ages_discovery::modules::sound::system::{apply_audio_settings, on_sound_emit, toggle_mute, update_positional_sounds};
+use ages_discovery::modules::tech::components::{DiscoveryMade, EraReached, Notifications, PersonSprites, Progress};
+use ages_discovery::modules::tech::systems::{apply_person_sprites, load_tech_tree, make_discoveries, notify_progress, track_progress, update_notifications};
+use ages_discovery::modules::world_state::components::WorldState;
+use ages_discovery::modules::world_state::systems::{record_entity_state, restore_entity_state};
+use ages_discovery::modules::worldgen::components::GeneratedLevels;
+use ages_discovery::modules::worldgen::systems::generate_procedural_levels;
 
 fn main() {
     App::new()
@@ -29,18 +65,102 @@ fn main() {
         .add_plugin(DebugPlugin)
         // .add_plugin(LogDiagnosticsPlugin::default())
         // .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_audio_channel::<MusicChannel>()
+        .add_audio_channel::<SfxChannel>()
+        .add_audio_channel::<AmbienceChannel>()
+        .add_audio_channel::<UiChannel>()
         .add_event::<SoundEvent>()
+        .add_event::<SaveEvent>()
+        .add_event::<LoadEvent>()
+        .add_event::<DamageEvent>()
+        .add_event::<DeathEvent>()
+        .add_event::<Interacted>()
+        .add_event::<GatherEvent>()
+        .add_event::<InventoryChanged>()
+        .add_event::<Crafted>()
+        .add_event::<RecipeUnlocked>()
+        .add_event::<DiscoveryMade>()
+        .add_event::<StructurePlaced>()
+        .add_event::<ClockEvent>()
+        .add_event::<EraReached>()
         .insert_resource(Msaa {samples: 1})
-        .insert_resource(RngResource {..default()})
-        .insert_resource(LevelSelection::Index(0))
-        .register_ldtk_entity::<PlayerBundle>("Player")
-        .register_ldtk_entity::<TreeStumpBundle>("Tree_Stump")
-        .register_ldtk_entity::<RockBundle>("Rock")
+        .insert_resource(RngResource::load())
+        .insert_resource(PlayingSounds {..default()})
+        .insert_resource(AudioSettings::load())
+        .insert_resource(MusicController {..default()})
+        .insert_resource(MusicDucking {..default()})
+        .insert_resource(LevelSelection::Identifier(START_LEVEL_IDENTIFIER.to_string()))
+        .insert_resource(LdtkSettings {
+            level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation { load_level_neighbors: true },
+            ..default()
+        })
+        .insert_resource(LevelTransition {..default()})
+        .insert_resource(LastCheckpoint {..default()})
+        .insert_resource(WorldState {..default()})
+        .insert_resource(ActiveSlot {..default()})
+        .insert_resource(Playtime {..default()})
+        .insert_resource(HotReloadState {..default()})
+        .insert_resource(GeneratedLevels {..default()})
+        .insert_resource(BehaviourTrees {..default()})
+        .insert_resource(NavGrid {..default()})
+        .insert_resource(PathRequests {..default()})
+        .insert_resource(PathCache {..default()})
+        .insert_resource(AnimalRegistry::load())
+        .insert_resource(InteractionFocus {..default()})
+        .insert_resource(GatheringNodes::load())
+        .insert_resource(ItemDatabase::load())
+        .insert_resource(CraftingMenu {..default()})
+        .insert_resource(OpenMenus {..default()})
+        .insert_resource(Progress {..default()})
+        .insert_resource(PersonSprites {..default()})
+        .insert_resource(Notifications {..default()})
+        .insert_resource(PlacedStructures {..default()})
+        .insert_resource(BuildMode {..default()})
+        .insert_resource(WorldClock::load())
+        .insert_resource(Daylight {..default()})
+        .register_level_entities()
+        .register_ldtk_int_cell::<BlockedCellBundle>(COLLISION_INT_GRID_VALUE)
+        .add_startup_system_to_stage(StartupStage::PreStartup, load_recipes)
+        .add_startup_system_to_stage(StartupStage::PreStartup, load_structures.after(load_recipes))
+        .add_startup_system_to_stage(StartupStage::PreStartup, load_tech_tree.after(load_structures))
         .add_startup_system(setup_game)
+        .add_system_to_stage(CoreStage::PreUpdate, generate_procedural_levels.before(LdtkSystemLabel::LevelSpawning))
+        .add_system_to_stage(CoreStage::First, detach_player_on_reload)
+        .add_system_to_stage(CoreStage::First, restore_sprite_colors)
+        .add_system_to_stage(CoreStage::PostUpdate, tint_sprites)
+        .add_system_to_stage(CoreStage::PostUpdate, tint_tiles)
+        .add_system(reattach_player_after_reload)
         .add_system(read_input)
+        .add_system(enter_doors)
+        .add_system(update_level_transition.after(enter_doors))
+        .add_system(despawn_duplicate_players)
+        .add_system(select_level_by_player_position.after(update_level_transition))
+        .add_system(reach_checkpoints.after(update_level_transition))
+        .add_system(attack.after(update_sideds_by_direction))
+        .add_system(update_attacking)
+        .add_system(apply_damage.after(attack))
+        .add_system(update_hurt.after(apply_damage).after(update_movement_sided_sprite).after(update_gathering).after(tick_brains))
+        .add_system(handle_deaths.after(apply_damage))
+        .add_system(update_stamina.after(read_input))
+        .add_system(update_stamina_bar.after(update_stamina))
         .add_system(on_sound_emit)
+        .add_system(update_positional_sounds.after(on_sound_emit))
+        .add_system(update_music)
+        .add_system(duck_music.after(on_sound_emit))
+        .add_system(toggle_mute)
+        .add_system(apply_audio_settings.after(duck_music).after(toggle_mute))
         .add_system(sync_self_physical_multiple_movement)
-        .add_system(collider_direction_react.after(read_input))
+        .add_system(update_nav_grid)
+        .add_system(process_path_requests.after(update_nav_grid))
+        .add_system(update_behaviours)
+        .add_system(attach_brains)
+        .add_system(update_perception)
+        .add_system(tick_brains.after(update_perception).after(attach_brains))
+        .add_system(spawn_level_animals)
+        .add_system(spawn_placed_animals)
+        .add_system(update_animal_activity.after(advance_clock))
+        .add_system(update_animals.after(update_animal_activity))
+        .add_system(collider_direction_react.after(read_input).after(update_behaviours).after(tick_brains).after(update_animals))
         .add_system(direction_react.after(collider_direction_react))
         .add_system(overlap_sprite_zones.after(direction_react))
         .add_system(update_movement_state_by_direction.after(collider_direction_react))
@@ -48,5 +168,44 @@ fn main() {
         .add_system(update_sideds_by_direction.after(direction_react))
         .add_system(update_sided_sprite.after(update_sideds_by_direction))
         .add_system(camera_follow.after(update_sided_sprite))
+        .add_system(update_interaction_focus.after(update_sideds_by_direction))
+        .add_system(read_interact_input.after(update_interaction_focus))
+        .add_system(update_interact_prompt.after(update_interaction_focus))
+        .add_system(attach_harvestables)
+        .add_system(gather.after(read_interact_input))
+        .add_system(regrow_harvestables)
+        .add_system(update_harvestable_prompts.before(update_interaction_focus))
+        .add_system(update_harvestable_sprites.after(gather))
+        .add_system(update_gathering.after(gather).after(update_movement_sided_sprite))
+        .add_system(drop_gathered_items.after(gather))
+        .add_system(drop_loot.after(apply_damage).before(handle_deaths))
+        .add_system(pick_up_items)
+        .add_system(update_carried_tools.after(pick_up_items))
+        .add_system(spawn_placed_workstations)
+        .add_system(toggle_crafting_menu.after(read_interact_input))
+        .add_system(queue_recipes.after(toggle_crafting_menu))
+        .add_system(update_crafting_queues.after(queue_recipes))
+        .add_system(unlock_recipes.after(update_crafting_queues).after(pick_up_items))
+        .add_system(update_workstation_sprites.after(update_crafting_queues))
+        .add_system(update_crafting_menu.after(update_crafting_queues).after(unlock_recipes).after(make_discoveries))
+        .add_system(track_progress.after(update_crafting_queues).after(pick_up_items))
+        .add_system(make_discoveries.after(track_progress))
+        .add_system(notify_progress.after(make_discoveries).after(unlock_recipes))
+        .add_system(update_notifications.after(notify_progress))
+        .add_system(apply_person_sprites.after(make_discoveries))
+        .add_system(spawn_level_structures)
+        .add_system(toggle_build_mode.after(toggle_crafting_menu))
+        .add_system(update_build_placement.after(toggle_build_mode).after(update_sideds_by_direction).after(make_discoveries))
+        .add_system(place_structure.after(update_build_placement))
+        .add_system(update_build_ghost.after(place_structure))
+        .add_system(update_build_menu.after(place_structure))
+        .add_system(advance_clock.after(toggle_build_mode))
+        .add_system(update_daylight.after(advance_clock))
+        .add_system(restore_entity_state)
+        .add_system(record_entity_state.after(direction_react))
+        .add_system(tick_playtime)
+        .add_system(read_save_input)
+        .add_system(save_game.after(read_save_input).after(update_level_transition))
+        .add_system(load_game.after(read_save_input))
         .run();
 }
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::prelude::TimerMode;
+use bevy::time::Timer;
+use bevy::utils::default;
+use bevy_ecs_ldtk::EntityInstance;
+use crate::modules::ai::consts::{AI_DATA_PATH, DEFAULT_HEARING_DISTANCE, DEFAULT_SIGHT_ANGLE, DEFAULT_SIGHT_DISTANCE, HEARING_MEMORY_SECONDS};
+use crate::modules::ai::tree::{BehaviourTree, NodeDefinition, NodeMemory};
+use crate::modules::ai::validation::validate_tree;
+use crate::modules::level::consts::{HEARING_DISTANCE_FIELD, SIGHT_ANGLE_FIELD, SIGHT_DISTANCE_FIELD};
+use crate::modules::level::fields::entity_float;
+use crate::modules::storage::files::read_ron;
+
+#[derive(Default, Resource)]
+pub struct BehaviourTrees {
+    pub trees: HashMap<String, BehaviourTree>,
+}
+
+impl BehaviourTrees {
+    pub fn load(&mut self, name: &str) -> Option<&BehaviourTree> {
+        if !self.trees.contains_key(name) {
+            let path = format!("{}/{}.ron", AI_DATA_PATH, name);
+            match read_ron::<NodeDefinition>(&path) {
+                Some(definition) => {
+                    let errors = validate_tree(&definition);
+                    for error in errors.iter() {
+                        warn!("Behaviour tree {} {}", path, error);
+                    }
+                    if !errors.is_empty() {
+                        return None;
+                    }
+                    self.trees.insert(name.to_string(), BehaviourTree::from(definition));
+                }
+                None => {
+                    warn!("Could not load behaviour tree {}", path);
+                    return None;
+                }
+            }
+        }
+        self.trees.get(name)
+    }
+}
+
+#[derive(Component)]
+pub struct Brain {
+    pub tree: String,
+    pub memory: Vec<NodeMemory>,
+    pub home: Option<Vec2>,
+}
+
+#[derive(Component)]
+pub struct Perception {
+    pub sight_distance: f32,
+    // full width of the sight cone in degrees
+    pub sight_angle: f32,
+    pub hearing_distance: f32,
+    pub sees_player: bool,
+    pub heard: Option<Vec2>,
+    pub heard_timer: Timer,
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Perception {
+            sight_distance: DEFAULT_SIGHT_DISTANCE,
+            sight_angle: DEFAULT_SIGHT_ANGLE,
+            hearing_distance: DEFAULT_HEARING_DISTANCE,
+            sees_player: false,
+            heard: None,
+            heard_timer: Timer::from_seconds(HEARING_MEMORY_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl From<EntityInstance> for Perception {
+    fn from(entity_instance: EntityInstance) -> Perception {
+        let perception = Perception {..default()};
+        Perception {
+            sight_distance: entity_float(&entity_instance, SIGHT_DISTANCE_FIELD).unwrap_or(perception.sight_distance),
+            sight_angle: entity_float(&entity_instance, SIGHT_ANGLE_FIELD).unwrap_or(perception.sight_angle),
+            hearing_distance: entity_float(&entity_instance, HEARING_DISTANCE_FIELD).unwrap_or(perception.hearing_distance),
+            ..perception
+        }
+    }
+}
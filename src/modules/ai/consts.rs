@@ -0,0 +1,9 @@
+pub const AI_DATA_PATH: &str = "assets/data/ai";
+
+pub const ARRIVAL_DISTANCE: f32 = 3.;
+pub const MOVE_TIMEOUT_SECONDS: f32 = 6.;
+
+pub const DEFAULT_SIGHT_DISTANCE: f32 = 80.;
+pub const DEFAULT_SIGHT_ANGLE: f32 = 120.;
+pub const DEFAULT_HEARING_DISTANCE: f32 = 64.;
+pub const HEARING_MEMORY_SECONDS: f32 = 3.;
@@ -0,0 +1,5 @@
+pub mod components;
+pub mod consts;
+pub mod tree;
+pub mod systems;
+pub mod validation;
\ No newline at end of file
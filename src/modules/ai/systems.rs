@@ -0,0 +1,171 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Added, Commands, Entity, EventReader, EventWriter, GlobalTransform, Query, Res, ResMut, TextureAtlasSprite, Time, With, Without};
+use bevy::utils::default;
+use bevy_ecs_ldtk::EntityInstance;
+use crate::modules::ai::components::{BehaviourTrees, Brain, Perception};
+use crate::modules::ai::tree::{segment_hits_box, AiOutput, Blackboard};
+use crate::modules::clock::components::WorldClock;
+use crate::modules::level::consts::BRAIN_FIELD;
+use crate::modules::level::fields::entity_string;
+use crate::modules::living::components::Player;
+use crate::modules::navigation::components::{NavGrid, NavPath, PathRequests};
+use crate::modules::navigation::systems::steer;
+use crate::modules::npc::components::Behaviour;
+use crate::modules::physics::components::{Collider, SelfPhysical};
+use crate::modules::physics::sprite_change::components::{AnimationOverride, MultipleSided, Side};
+use crate::modules::rng::components::{RngResource, RngStream};
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
+
+pub fn attach_brains(
+    mut commands: Commands, mut trees: ResMut<BehaviourTrees>,
+    added: Query<(Entity, &EntityInstance), Added<EntityInstance>>
+) {
+    for (entity, entity_instance) in added.iter() {
+        let tree = match entity_string(entity_instance, BRAIN_FIELD) {
+            Some(tree) => tree,
+            None => continue
+        };
+        let memory = match trees.load(&tree) {
+            Some(behaviour_tree) => behaviour_tree.memory(),
+            None => continue
+        };
+        // a brain replaces the simple villager behaviour
+        commands.entity(entity)
+            .remove::<Behaviour>()
+            .insert((Brain { tree, memory, home: None }, Perception::from(entity_instance.clone())));
+    }
+}
+
+pub fn update_perception(
+    time: Res<Time>, mut sound_events: EventReader<SoundEvent>, emitters: Query<&GlobalTransform>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>, colliders: Query<(Entity, &GlobalTransform, &Collider)>,
+    mut agents: Query<(Entity, &mut Perception, &GlobalTransform, Option<&MultipleSided>)>
+) {
+    let sounds: Vec<(Entity, Vec2)> = sound_events.iter()
+        .filter_map(|sound_event| sound_event.emitter)
+        .filter_map(|emitter| emitters.get(emitter).ok().map(|transform| (emitter, transform.translation().truncate())))
+        .collect();
+    let player = players.get_single().ok().map(|(entity, transform)| (entity, transform.translation().truncate()));
+
+    for (entity, mut perception, transform, multiple_sided) in agents.iter_mut() {
+        let position = transform.translation().truncate();
+        let sees_player = player.is_some_and(|(player_entity, player_position)| {
+            in_sight_cone(&perception, position, multiple_sided.map(|sided| sided.side.direction()), player_position)
+                && !sight_blocked(&colliders, position, player_position, [entity, player_entity])
+        });
+        if perception.sees_player != sees_player {
+            perception.sees_player = sees_player;
+        }
+
+        if perception.heard.is_some() && perception.heard_timer.tick(time.delta()).finished() {
+            perception.heard = None;
+        }
+        let heard = sounds.iter()
+            .filter(|(emitter, _)| *emitter != entity)
+            .find(|(_, sound_position)| sound_position.distance(position) <= perception.hearing_distance);
+        if let Some((_, sound_position)) = heard {
+            perception.heard = Some(*sound_position);
+            perception.heard_timer.reset();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn tick_brains(
+    mut commands: Commands, time: Res<Time>, clock: Res<WorldClock>, trees: Res<BehaviourTrees>, mut rng: ResMut<RngResource>,
+    mut sound_events: EventWriter<SoundEvent>, grid: Res<NavGrid>, mut requests: ResMut<PathRequests>,
+    players: Query<&GlobalTransform, With<Player>>,
+    mut agents: Query<(
+        Entity, &mut Brain, &Perception, &GlobalTransform, &mut SelfPhysical, Option<&mut MultipleSided>,
+        Option<&mut TextureAtlasSprite>, Option<&AnimationOverride>, Option<&mut NavPath>
+    ), Without<Player>>
+) {
+    let player = players.get_single().ok().map(|player| player.translation().truncate());
+    for (entity, mut brain, perception, transform, mut self_physical, multiple_sided, sprite, animation_override, mut nav_path) in agents.iter_mut() {
+        let Brain { tree, memory, home } = &mut *brain;
+        let tree = match trees.trees.get(tree) {
+            Some(tree) => tree,
+            None => continue
+        };
+        let position = transform.translation().truncate();
+        let blackboard = Blackboard {
+            position,
+            home: *home.get_or_insert(position),
+            player,
+            sees_player: perception.sees_player,
+            heard: perception.heard,
+            night: clock.is_night(),
+        };
+        let mut output = AiOutput {..default()};
+        tree.tick(memory, &blackboard, time.delta_seconds(), rng.stream(RngStream::AI), &mut output);
+
+        if let Some(destination) = output.destination {
+            output.direction = steer(entity, position, destination, nav_path.as_deref_mut(), &grid, &mut requests)
+                .unwrap_or(Vec2::ZERO);
+        }
+        let direction = output.direction.extend(0.);
+        if self_physical.direction != direction {
+            self_physical.direction = direction;
+        }
+        if let (Some(look_at), Some(mut multiple_sided), true) = (output.look_at, multiple_sided, output.direction == Vec2::ZERO) {
+            let side = side_towards(look_at - position);
+            if multiple_sided.side != side {
+                multiple_sided.side = side;
+            }
+        }
+        match (output.frame, sprite) {
+            (Some(frame), Some(mut sprite)) => {
+                sprite.index = frame;
+                if animation_override.is_none() {
+                    commands.entity(entity).insert(AnimationOverride);
+                }
+            }
+            _ => {
+                if animation_override.is_some() {
+                    commands.entity(entity).remove::<AnimationOverride>();
+                }
+            }
+        }
+        for sound in output.sounds {
+            sound_events.send(SoundEvent {
+                path: sound.path,
+                sound_type: if sound.random { SoundType::RANDOM } else { SoundType::FIXED },
+                file: sound.file,
+                channel: SoundChannel::SFX,
+                emitter: Some(entity),
+            });
+        }
+    }
+}
+
+fn side_towards(direction: Vec2) -> Side {
+    if direction.x.abs() >= direction.y.abs() {
+        if direction.x > 0. { Side::RIGHT } else { Side::LEFT }
+    } else if direction.y > 0. {
+        Side::TOP
+    } else {
+        Side::BOTTOM
+    }
+}
+
+fn in_sight_cone(perception: &Perception, position: Vec2, facing: Option<Vec2>, target: Vec2) -> bool {
+    let offset = target - position;
+    if offset.length() > perception.sight_distance {
+        return false;
+    }
+    match facing {
+        Some(facing) => facing.angle_between(offset).abs().to_degrees() <= perception.sight_angle / 2.,
+        None => true
+    }
+}
+
+fn sight_blocked(
+    colliders: &Query<(Entity, &GlobalTransform, &Collider)>, from: Vec2, to: Vec2, ignored: [Entity; 2]
+) -> bool {
+    colliders.iter()
+        .filter(|(entity, _, _)| !ignored.contains(entity))
+        .any(|(_, transform, collider)| {
+            let min = transform.translation().truncate() + collider.0.offset;
+            segment_hits_box(from, to, min, min + collider.0.size)
+        })
+}
@@ -0,0 +1,523 @@
+use bevy::math::Vec2;
+use rand::Rng;
+use serde::Deserialize;
+use crate::modules::ai::consts::{ARRIVAL_DISTANCE, MOVE_TIMEOUT_SECONDS};
+
+// data file layout, flattened into a BehaviourTree once loaded
+#[derive(Deserialize, Clone, Debug)]
+pub enum NodeDefinition {
+    Sequence(Vec<NodeDefinition>),
+    Selector(Vec<NodeDefinition>),
+    Utility(Vec<UtilityOption>),
+    Inverter(Box<NodeDefinition>),
+    Condition(Condition),
+    Action(Action),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UtilityOption {
+    pub considerations: Vec<Consideration>,
+    pub node: NodeDefinition,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum Consideration {
+    Constant(f32),
+    SeesPlayer,
+    HeardSound,
+    // 1 at `near` or closer, 0 at `far` or further
+    PlayerDistance { near: f32, far: f32 },
+    Night,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum Condition {
+    SeesPlayer,
+    HeardSound,
+    PlayerWithin(f32),
+    AtHome(f32),
+    Night,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum Action {
+    MoveTo(Target),
+    Wait(f32),
+    PlayAnimation { frames: Vec<usize>, frame_seconds: f32 },
+    EmitSound { path: String, file: String, random: bool },
+    LookAt(Target),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum Target {
+    Player,
+    Home,
+    LastHeard,
+    Wander(f32),
+    AwayFromPlayer(f32),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status { RUNNING, SUCCESS, FAILURE }
+
+pub enum NodeKind {
+    Sequence,
+    Selector,
+    Utility(Vec<Vec<Consideration>>),
+    Inverter,
+    Condition(Condition),
+    Action(Action),
+}
+
+pub struct Node {
+    pub kind: NodeKind,
+    pub children: Vec<usize>,
+    // end of this node's subtree in the pre-order node list
+    pub end: usize,
+}
+
+#[derive(Default, Clone)]
+pub struct NodeMemory {
+    pub cursor: usize,
+    pub elapsed: f32,
+    pub target: Option<Vec2>,
+}
+
+// everything an agent knows this tick, in world space
+pub struct Blackboard {
+    pub position: Vec2,
+    pub home: Vec2,
+    pub player: Option<Vec2>,
+    pub sees_player: bool,
+    pub heard: Option<Vec2>,
+    pub night: bool,
+}
+
+pub struct SoundCue {
+    pub path: String,
+    pub file: String,
+    pub random: bool,
+}
+
+#[derive(Default)]
+pub struct AiOutput {
+    pub direction: Vec2,
+    // where a move is heading, lets the caller path around obstacles
+    pub destination: Option<Vec2>,
+    pub look_at: Option<Vec2>,
+    pub frame: Option<usize>,
+    pub sounds: Vec<SoundCue>,
+}
+
+pub struct BehaviourTree {
+    pub nodes: Vec<Node>,
+}
+
+impl From<NodeDefinition> for BehaviourTree {
+    fn from(definition: NodeDefinition) -> BehaviourTree {
+        let mut tree = BehaviourTree { nodes: Vec::new() };
+        tree.push(definition);
+        tree
+    }
+}
+
+impl BehaviourTree {
+    fn push(&mut self, definition: NodeDefinition) -> usize {
+        let index = self.nodes.len();
+        let (kind, children) = match definition {
+            NodeDefinition::Sequence(children) => (NodeKind::Sequence, children),
+            NodeDefinition::Selector(children) => (NodeKind::Selector, children),
+            NodeDefinition::Utility(options) => {
+                let (considerations, children) = options.into_iter()
+                    .map(|option| (option.considerations, option.node))
+                    .unzip();
+                (NodeKind::Utility(considerations), children)
+            }
+            NodeDefinition::Inverter(child) => (NodeKind::Inverter, vec![*child]),
+            NodeDefinition::Condition(condition) => (NodeKind::Condition(condition), Vec::new()),
+            NodeDefinition::Action(action) => (NodeKind::Action(action), Vec::new()),
+        };
+        self.nodes.push(Node { kind, children: Vec::new(), end: index + 1 });
+        let children = children.into_iter().map(|child| self.push(child)).collect();
+        self.nodes[index].children = children;
+        self.nodes[index].end = self.nodes.len();
+        index
+    }
+
+    pub fn memory(&self) -> Vec<NodeMemory> {
+        vec![NodeMemory::default(); self.nodes.len()]
+    }
+
+    // pure so trees can be ticked without an App
+    pub fn tick<R: Rng>(
+        &self, memory: &mut [NodeMemory], blackboard: &Blackboard, delta: f32, rng: &mut R, output: &mut AiOutput
+    ) -> Status {
+        if self.nodes.is_empty() {
+            return Status::FAILURE;
+        }
+        self.tick_node(0, memory, blackboard, delta, rng, output)
+    }
+
+    fn reset(&self, index: usize, memory: &mut [NodeMemory]) {
+        for node_memory in memory[index..self.nodes[index].end].iter_mut() {
+            *node_memory = NodeMemory::default();
+        }
+    }
+
+    fn tick_node<R: Rng>(
+        &self, index: usize, memory: &mut [NodeMemory], blackboard: &Blackboard, delta: f32, rng: &mut R,
+        output: &mut AiOutput
+    ) -> Status {
+        let node = &self.nodes[index];
+        match &node.kind {
+            NodeKind::Sequence | NodeKind::Selector => {
+                let continue_on = match node.kind {
+                    NodeKind::Sequence => Status::SUCCESS,
+                    _ => Status::FAILURE,
+                };
+                while memory[index].cursor < node.children.len() {
+                    let child = node.children[memory[index].cursor];
+                    let status = self.tick_node(child, memory, blackboard, delta, rng, output);
+                    if status == Status::RUNNING {
+                        return Status::RUNNING;
+                    }
+                    if status != continue_on {
+                        memory[index].cursor = 0;
+                        return status;
+                    }
+                    memory[index].cursor += 1;
+                }
+                memory[index].cursor = 0;
+                continue_on
+            }
+            NodeKind::Utility(considerations) => {
+                let best = considerations.iter().enumerate()
+                    .map(|(option, considerations)| (option, score(considerations, blackboard)))
+                    .filter(|(_, score)| *score > 0.)
+                    .fold(None, |best: Option<(usize, f32)>, (option, score)| match best {
+                        Some((_, best_score)) if best_score >= score => best,
+                        _ => Some((option, score))
+                    });
+                // cursor holds the running option plus one, zero meaning none
+                let previous = memory[index].cursor;
+                let chosen = match best {
+                    Some((option, _)) => option,
+                    None => {
+                        if previous > 0 {
+                            self.reset(node.children[previous - 1], memory);
+                        }
+                        memory[index].cursor = 0;
+                        return Status::FAILURE;
+                    }
+                };
+                if previous > 0 && previous != chosen + 1 {
+                    self.reset(node.children[previous - 1], memory);
+                }
+                let status = self.tick_node(node.children[chosen], memory, blackboard, delta, rng, output);
+                memory[index].cursor = if status == Status::RUNNING { chosen + 1 } else { 0 };
+                status
+            }
+            NodeKind::Inverter => {
+                match self.tick_node(node.children[0], memory, blackboard, delta, rng, output) {
+                    Status::SUCCESS => Status::FAILURE,
+                    Status::FAILURE => Status::SUCCESS,
+                    Status::RUNNING => Status::RUNNING,
+                }
+            }
+            NodeKind::Condition(condition) => {
+                if check(condition, blackboard) {
+                    Status::SUCCESS
+                } else {
+                    Status::FAILURE
+                }
+            }
+            NodeKind::Action(action) => act(action, &mut memory[index], blackboard, delta, rng, output),
+        }
+    }
+}
+
+fn score(considerations: &[Consideration], blackboard: &Blackboard) -> f32 {
+    considerations.iter().map(|consideration| match consideration {
+        Consideration::Constant(value) => *value,
+        Consideration::SeesPlayer => if blackboard.sees_player { 1. } else { 0. },
+        Consideration::HeardSound => if blackboard.heard.is_some() { 1. } else { 0. },
+        Consideration::PlayerDistance { near, far } => match blackboard.player {
+            Some(player) => {
+                let distance = player.distance(blackboard.position);
+                (1. - (distance - near) / (far - near).max(f32::EPSILON)).clamp(0., 1.)
+            }
+            None => 0.
+        },
+        Consideration::Night => if blackboard.night { 1. } else { 0. },
+    }).product()
+}
+
+fn check(condition: &Condition, blackboard: &Blackboard) -> bool {
+    match condition {
+        Condition::SeesPlayer => blackboard.sees_player,
+        Condition::HeardSound => blackboard.heard.is_some(),
+        Condition::PlayerWithin(distance) => blackboard.player
+            .is_some_and(|player| player.distance(blackboard.position) <= *distance),
+        Condition::AtHome(distance) => blackboard.home.distance(blackboard.position) <= *distance,
+        Condition::Night => blackboard.night,
+    }
+}
+
+fn resolve<R: Rng>(target: &Target, blackboard: &Blackboard, rng: &mut R) -> Option<Vec2> {
+    match target {
+        Target::Player => blackboard.player,
+        Target::Home => Some(blackboard.home),
+        Target::LastHeard => blackboard.heard,
+        Target::Wander(radius) => {
+            let angle = rng.gen_range(0. ..std::f32::consts::TAU);
+            let distance = rng.gen_range(0. ..=*radius);
+            Some(blackboard.home + Vec2::new(angle.cos(), angle.sin()) * distance)
+        }
+        Target::AwayFromPlayer(distance) => blackboard.player.map(|player| {
+            blackboard.position + (blackboard.position - player).normalize_or_zero() * *distance
+        }),
+    }
+}
+
+fn act<R: Rng>(
+    action: &Action, memory: &mut NodeMemory, blackboard: &Blackboard, delta: f32, rng: &mut R, output: &mut AiOutput
+) -> Status {
+    match action {
+        Action::MoveTo(target) => {
+            if memory.target.is_none() {
+                memory.target = resolve(target, blackboard, rng);
+            }
+            let target = match memory.target {
+                Some(target) => target,
+                None => return Status::FAILURE
+            };
+            let offset = target - blackboard.position;
+            if offset.length() <= ARRIVAL_DISTANCE {
+                *memory = NodeMemory::default();
+                return Status::SUCCESS;
+            }
+            memory.elapsed += delta;
+            if memory.elapsed >= MOVE_TIMEOUT_SECONDS {
+                *memory = NodeMemory::default();
+                return Status::FAILURE;
+            }
+            output.direction = offset.normalize_or_zero();
+            output.destination = Some(target);
+            Status::RUNNING
+        }
+        Action::Wait(seconds) => {
+            memory.elapsed += delta;
+            if memory.elapsed >= *seconds {
+                *memory = NodeMemory::default();
+                return Status::SUCCESS;
+            }
+            Status::RUNNING
+        }
+        Action::PlayAnimation { frames, frame_seconds } => {
+            let frame = (memory.elapsed / frame_seconds.max(f32::EPSILON)) as usize;
+            memory.elapsed += delta;
+            match frames.get(frame) {
+                Some(frame) => {
+                    output.frame = Some(*frame);
+                    Status::RUNNING
+                }
+                None => {
+                    *memory = NodeMemory::default();
+                    Status::SUCCESS
+                }
+            }
+        }
+        Action::EmitSound { path, file, random } => {
+            output.sounds.push(SoundCue { path: path.clone(), file: file.clone(), random: *random });
+            Status::SUCCESS
+        }
+        Action::LookAt(target) => match resolve(target, blackboard, rng) {
+            Some(target) => {
+                output.look_at = Some(target);
+                Status::SUCCESS
+            }
+            None => Status::FAILURE
+        },
+    }
+}
+
+// slab test of the segment against an axis aligned box
+pub fn segment_hits_box(from: Vec2, to: Vec2, min: Vec2, max: Vec2) -> bool {
+    let delta = to - from;
+    let mut enter: f32 = 0.;
+    let mut exit: f32 = 1.;
+    for (start, direction, low, high) in [(from.x, delta.x, min.x, max.x), (from.y, delta.y, min.y, max.y)] {
+        if direction.abs() < f32::EPSILON {
+            if start < low || start > high {
+                return false;
+            }
+            continue;
+        }
+        let first = (low - start) / direction;
+        let second = (high - start) / direction;
+        enter = enter.max(first.min(second));
+        exit = exit.min(first.max(second));
+        if enter > exit {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use super::*;
+
+    fn blackboard() -> Blackboard {
+        Blackboard { position: Vec2::ZERO, home: Vec2::ZERO, player: None, sees_player: false, heard: None, night: false }
+    }
+
+    fn tick(tree: &BehaviourTree, memory: &mut [NodeMemory], blackboard: &Blackboard, delta: f32) -> (Status, AiOutput) {
+        let mut output = AiOutput {..Default::default()};
+        let status = tree.tick(memory, blackboard, delta, &mut ChaCha12Rng::seed_from_u64(0), &mut output);
+        (status, output)
+    }
+
+    fn sound() -> NodeDefinition {
+        NodeDefinition::Action(Action::EmitSound { path: "step/grass".to_string(), file: "1".to_string(), random: false })
+    }
+
+    #[test]
+    fn sequence_stops_at_the_first_failure() {
+        let tree = BehaviourTree::from(NodeDefinition::Sequence(vec![NodeDefinition::Condition(Condition::SeesPlayer), sound()]));
+        let mut memory = tree.memory();
+        let (status, output) = tick(&tree, &mut memory, &blackboard(), 0.1);
+        assert_eq!(status, Status::FAILURE);
+        assert!(output.sounds.is_empty());
+
+        let (status, output) = tick(&tree, &mut memory, &Blackboard { sees_player: true, ..blackboard() }, 0.1);
+        assert_eq!(status, Status::SUCCESS);
+        assert_eq!(output.sounds.len(), 1);
+    }
+
+    #[test]
+    fn sequence_resumes_at_the_running_child() {
+        let tree = BehaviourTree::from(NodeDefinition::Sequence(vec![sound(), NodeDefinition::Action(Action::Wait(1.)), sound()]));
+        let mut memory = tree.memory();
+        let (status, output) = tick(&tree, &mut memory, &blackboard(), 0.6);
+        assert_eq!(status, Status::RUNNING);
+        assert_eq!(output.sounds.len(), 1);
+
+        let (status, output) = tick(&tree, &mut memory, &blackboard(), 0.6);
+        assert_eq!(status, Status::SUCCESS);
+        assert_eq!(output.sounds.len(), 1);
+        assert_eq!(memory[0].cursor, 0);
+    }
+
+    #[test]
+    fn selector_stops_at_the_first_success() {
+        let tree = BehaviourTree::from(NodeDefinition::Selector(vec![NodeDefinition::Condition(Condition::Night), sound()]));
+        let mut memory = tree.memory();
+        let (status, output) = tick(&tree, &mut memory, &Blackboard { night: true, ..blackboard() }, 0.1);
+        assert_eq!(status, Status::SUCCESS);
+        assert!(output.sounds.is_empty());
+
+        let (status, output) = tick(&tree, &mut memory, &blackboard(), 0.1);
+        assert_eq!(status, Status::SUCCESS);
+        assert_eq!(output.sounds.len(), 1);
+
+        let tree = BehaviourTree::from(NodeDefinition::Selector(vec![
+            NodeDefinition::Condition(Condition::Night), NodeDefinition::Condition(Condition::SeesPlayer)
+        ]));
+        let mut memory = tree.memory();
+        assert_eq!(tick(&tree, &mut memory, &blackboard(), 0.1).0, Status::FAILURE);
+    }
+
+    #[test]
+    fn utility_runs_the_best_scoring_option() {
+        let tree = BehaviourTree::from(NodeDefinition::Utility(vec![
+            UtilityOption { considerations: vec![Consideration::Constant(0.2)], node: NodeDefinition::Action(Action::LookAt(Target::Home)) },
+            UtilityOption {
+                considerations: vec![Consideration::SeesPlayer, Consideration::PlayerDistance { near: 10., far: 50. }],
+                node: NodeDefinition::Action(Action::LookAt(Target::Player))
+            },
+        ]));
+        let mut memory = tree.memory();
+        let player = Vec2::new(20., 0.);
+        let (status, output) = tick(&tree, &mut memory, &Blackboard { player: Some(player), ..blackboard() }, 0.1);
+        assert_eq!(status, Status::SUCCESS);
+        assert_eq!(output.look_at, Some(Vec2::ZERO));
+
+        let (status, output) = tick(&tree, &mut memory, &Blackboard { player: Some(player), sees_player: true, ..blackboard() }, 0.1);
+        assert_eq!(status, Status::SUCCESS);
+        assert_eq!(output.look_at, Some(player));
+    }
+
+    #[test]
+    fn utility_fails_when_nothing_scores() {
+        let tree = BehaviourTree::from(NodeDefinition::Utility(vec![
+            UtilityOption { considerations: vec![Consideration::Night], node: sound() },
+        ]));
+        let mut memory = tree.memory();
+        assert_eq!(tick(&tree, &mut memory, &blackboard(), 0.1).0, Status::FAILURE);
+    }
+
+    #[test]
+    fn player_distance_scores_between_near_and_far() {
+        let considerations = [Consideration::PlayerDistance { near: 10., far: 50. }];
+        let at = |x: f32| score(&considerations, &Blackboard { player: Some(Vec2::new(x, 0.)), ..blackboard() });
+        assert_eq!(at(5.), 1.);
+        assert_eq!(at(30.), 0.5);
+        assert_eq!(at(80.), 0.);
+        assert_eq!(score(&considerations, &blackboard()), 0.);
+    }
+
+    #[test]
+    fn inverter_flips_success_and_failure() {
+        let tree = BehaviourTree::from(NodeDefinition::Inverter(Box::new(NodeDefinition::Condition(Condition::Night))));
+        let mut memory = tree.memory();
+        assert_eq!(tick(&tree, &mut memory, &blackboard(), 0.1).0, Status::SUCCESS);
+        assert_eq!(tick(&tree, &mut memory, &Blackboard { night: true, ..blackboard() }, 0.1).0, Status::FAILURE);
+
+        let tree = BehaviourTree::from(NodeDefinition::Inverter(Box::new(NodeDefinition::Action(Action::Wait(1.)))));
+        let mut memory = tree.memory();
+        assert_eq!(tick(&tree, &mut memory, &blackboard(), 0.1).0, Status::RUNNING);
+    }
+
+    #[test]
+    fn move_to_arrives() {
+        let tree = BehaviourTree::from(NodeDefinition::Action(Action::MoveTo(Target::Home)));
+        let mut memory = tree.memory();
+        let away = Blackboard { position: Vec2::new(0., 40.), ..blackboard() };
+        let (status, output) = tick(&tree, &mut memory, &away, 0.1);
+        assert_eq!(status, Status::RUNNING);
+        assert_eq!(output.direction, Vec2::NEG_Y);
+        assert_eq!(output.destination, Some(Vec2::ZERO));
+
+        let (status, _) = tick(&tree, &mut memory, &Blackboard { position: Vec2::new(0., 1.), ..blackboard() }, 0.1);
+        assert_eq!(status, Status::SUCCESS);
+        assert!(memory[0].target.is_none());
+    }
+
+    #[test]
+    fn move_to_gives_up_after_the_timeout() {
+        let tree = BehaviourTree::from(NodeDefinition::Action(Action::MoveTo(Target::Home)));
+        let mut memory = tree.memory();
+        let away = Blackboard { position: Vec2::new(0., 40.), ..blackboard() };
+        assert_eq!(tick(&tree, &mut memory, &away, MOVE_TIMEOUT_SECONDS / 2.).0, Status::RUNNING);
+        let (status, output) = tick(&tree, &mut memory, &away, MOVE_TIMEOUT_SECONDS / 2.);
+        assert_eq!(status, Status::FAILURE);
+        assert_eq!(output.destination, None);
+        assert_eq!(memory[0].elapsed, 0.);
+        assert!(memory[0].target.is_none());
+    }
+
+    #[test]
+    fn segment_hits_box_only_when_crossing() {
+        let (min, max) = (Vec2::new(-1., -1.), Vec2::new(1., 1.));
+        assert!(segment_hits_box(Vec2::new(-5., 0.), Vec2::new(5., 0.), min, max));
+        assert!(segment_hits_box(Vec2::new(-5., -5.), Vec2::new(5., 5.), min, max));
+        assert!(segment_hits_box(Vec2::new(0., -5.), Vec2::new(0., 5.), min, max));
+        assert!(!segment_hits_box(Vec2::new(-5., 2.), Vec2::new(5., 2.), min, max));
+        assert!(!segment_hits_box(Vec2::new(2., -5.), Vec2::new(2., 5.), min, max));
+        assert!(!segment_hits_box(Vec2::new(-5., 0.), Vec2::new(-2., 0.), min, max));
+        assert!(!segment_hits_box(Vec2::new(-5., 2.), Vec2::new(2., -5.), min, max));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::modules::ai::tree::{Action, NodeDefinition, Target};
+
+pub fn validate_tree(definition: &NodeDefinition) -> Vec<String> {
+    let mut errors = Vec::new();
+    check_node(definition, &mut errors);
+    errors
+}
+
+fn check_node(definition: &NodeDefinition, errors: &mut Vec<String>) {
+    match definition {
+        NodeDefinition::Sequence(children) | NodeDefinition::Selector(children) => {
+            for child in children.iter() {
+                check_node(child, errors);
+            }
+        }
+        NodeDefinition::Utility(options) => {
+            for option in options.iter() {
+                check_node(&option.node, errors);
+            }
+        }
+        NodeDefinition::Inverter(child) => check_node(child, errors),
+        NodeDefinition::Action(Action::MoveTo(target) | Action::LookAt(target)) => check_target(target, errors),
+        NodeDefinition::Action(_) | NodeDefinition::Condition(_) => {}
+    }
+}
+
+fn check_target(target: &Target, errors: &mut Vec<String>) {
+    if let Target::Wander(radius) = target {
+        if !radius.is_finite() || *radius < 0. {
+            errors.push(format!("wanders within {}", radius));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wander_needs_a_radius() {
+        let wander = |radius: f32| NodeDefinition::Sequence(vec![NodeDefinition::Action(Action::MoveTo(Target::Wander(radius)))]);
+        assert!(validate_tree(&wander(48.)).is_empty());
+        assert!(validate_tree(&wander(0.)).is_empty());
+        assert_eq!(validate_tree(&wander(-1.)).len(), 1);
+        assert_eq!(validate_tree(&wander(f32::NAN)).len(), 1);
+    }
+}
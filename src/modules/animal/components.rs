@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::TimerMode;
+use bevy::sprite::TextureAtlas;
+use bevy::time::Timer;
+use bevy::utils::default;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+use serde::Deserialize;
+use crate::modules::animal::consts::{ANIMAL_DATA_PATH, ANIMAL_SHEET_COLUMNS, ANIMAL_SHEET_ROWS, GRAZE_MIN_SECONDS};
+use crate::modules::gathering::components::DropEntry;
+use crate::modules::level::consts::SPECIES_FIELD;
+use crate::modules::level::fields::entity_string;
+use crate::modules::living::components::{Health, Living};
+use crate::modules::physics::components::{Collider, Physical, SelfPhysical, SelfPhysicalBundle, TransformZone};
+use crate::modules::physics::sprite_change::components::{MultipleMovementState, MultipleSided, SpriteZone};
+use crate::modules::storage::files::read_ron;
+use crate::modules::worldgen::generator::Biome;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+pub enum Activity { #[default] ALWAYS, DIURNAL, NOCTURNAL, CREPUSCULAR }
+
+#[derive(Clone, Deserialize)]
+pub struct Species {
+    pub texture: String,
+    pub frame_size: (f32, f32),
+    pub speed: f32,
+    pub weight: f32,
+    pub health: f32,
+    pub flee_distance: f32,
+    // herd animals keep together and panic when a neighbour does
+    pub herd: bool,
+    pub activity: Activity,
+    // (width, height) at the animal's feet, animals without one fly over everything
+    #[serde(default)]
+    pub collider: Option<(f32, f32)>,
+    // dropped on death
+    #[serde(default)]
+    pub loot_rolls: (u32, u32),
+    #[serde(default)]
+    pub loot: Vec<DropEntry>,
+}
+
+impl Species {
+    pub fn collider(&self) -> Option<Collider> {
+        let (width, height) = self.collider?;
+        Some(Collider(TransformZone {
+            size: Vec2::new(width, height),
+            offset: Vec2::new(-width / 2., -self.frame_size.1 / 2.),
+        }))
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SpawnEntry {
+    pub species: String,
+    pub weight: f32,
+    pub group: (u32, u32),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SpawnTable {
+    // groups spawned in a level fully covered by the table
+    pub groups: u32,
+    pub entries: Vec<SpawnEntry>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct AnimalData {
+    pub species: HashMap<String, Species>,
+    pub levels: HashMap<String, SpawnTable>,
+    pub biomes: HashMap<Biome, SpawnTable>,
+}
+
+#[derive(Default, Resource)]
+pub struct AnimalRegistry {
+    pub data: AnimalData,
+    pub atlases: HashMap<String, Handle<TextureAtlas>>,
+}
+
+impl AnimalRegistry {
+    pub fn load() -> Self {
+        let data = read_ron(ANIMAL_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load animals from {}", ANIMAL_DATA_PATH);
+            AnimalData {..default()}
+        });
+        AnimalRegistry { data, ..default() }
+    }
+
+    pub fn atlas(
+        &mut self, name: &str, asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>
+    ) -> Option<Handle<TextureAtlas>> {
+        let species = self.data.species.get(name)?;
+        Some(self.atlases.entry(name.to_string()).or_insert_with(|| {
+            texture_atlases.add(TextureAtlas::from_grid(
+                asset_server.load(&species.texture), Vec2::new(species.frame_size.0, species.frame_size.1),
+                ANIMAL_SHEET_COLUMNS, ANIMAL_SHEET_ROWS, None, None
+            ))
+        }).clone())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AnimalState { #[default] GRAZE, ROAM, FLEE, REST }
+
+#[derive(Component)]
+pub struct Animal {
+    pub species: String,
+    pub state: AnimalState,
+    pub activity: Activity,
+    // cleared outside of the species' active hours
+    pub active: bool,
+    pub herd: bool,
+    pub flee_distance: f32,
+    pub home: Option<Vec2>,
+    pub heading: Vec2,
+    pub timer: Timer,
+}
+
+impl Animal {
+    pub fn enter(&mut self, state: AnimalState, heading: Vec2, seconds: f32) {
+        self.state = state;
+        self.heading = heading;
+        self.timer = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+}
+
+// placed in LDtk, replaced by the species' components once spawned
+#[derive(Default, Component)]
+pub struct AnimalSpawn {
+    pub species: Option<String>,
+}
+
+impl From<EntityInstance> for AnimalSpawn {
+    fn from(entity_instance: EntityInstance) -> AnimalSpawn {
+        AnimalSpawn { species: entity_string(&entity_instance, SPECIES_FIELD) }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct AnimalSpawnBundle {
+    #[from_entity_instance]
+    pub spawn: AnimalSpawn,
+}
+
+#[derive(Bundle)]
+pub struct AnimalBundle {
+    pub animal: Animal,
+    pub living: Living,
+    pub health: Health,
+    pub multiple_sided: MultipleSided,
+    pub multiple_movement_state: MultipleMovementState,
+    pub sprite_zone: SpriteZone,
+
+    #[bundle]
+    pub self_physical: SelfPhysicalBundle,
+}
+
+impl AnimalBundle {
+    pub fn new(name: &str, species: &Species) -> Self {
+        let size = Vec2::new(species.frame_size.0, species.frame_size.1);
+        AnimalBundle {
+            animal: Animal {
+                species: name.to_string(),
+                state: AnimalState::GRAZE,
+                activity: species.activity,
+                active: true,
+                herd: species.herd,
+                flee_distance: species.flee_distance,
+                home: None,
+                heading: Vec2::ZERO,
+                timer: Timer::from_seconds(GRAZE_MIN_SECONDS, TimerMode::Once),
+            },
+            living: Living,
+            health: Health { max: species.health, current: species.health },
+            multiple_sided: MultipleSided {..default()},
+            multiple_movement_state: MultipleMovementState {..default()},
+            sprite_zone: SpriteZone(TransformZone { size, offset: -size / 2. }),
+            self_physical: SelfPhysicalBundle {
+                physical: Physical { weight: species.weight, ..default() },
+                self_physical: SelfPhysical { direction: Vec3::ZERO, speed: species.speed, multiplier: 1. },
+            },
+        }
+    }
+}
@@ -0,0 +1,28 @@
+pub const ANIMAL_DATA_PATH: &str = "assets/data/animals.ron";
+
+pub const ANIMAL_SHEET_COLUMNS: usize = 5;
+pub const ANIMAL_SHEET_ROWS: usize = 3;
+pub const ANIMAL_Z: f32 = 3.;
+
+pub const HERD_RADIUS: f32 = 40.;
+pub const SEPARATION_RADIUS: f32 = 12.;
+pub const COHESION_WEIGHT: f32 = 0.6;
+pub const ALIGNMENT_WEIGHT: f32 = 0.5;
+pub const SEPARATION_WEIGHT: f32 = 1.4;
+pub const HOME_WEIGHT: f32 = 0.8;
+pub const ROAM_RADIUS: f32 = 64.;
+
+pub const GRAZE_MIN_SECONDS: f32 = 2.;
+pub const GRAZE_MAX_SECONDS: f32 = 6.;
+pub const ROAM_MIN_SECONDS: f32 = 1.;
+pub const ROAM_MAX_SECONDS: f32 = 3.;
+
+pub const FLEE_MULTIPLIER: f32 = 1.8;
+// animals keep running this long after the threat is out of range
+pub const CALM_SECONDS: f32 = 2.5;
+// flee distances are scaled by the player's speed multiplier, standing still counts as this
+pub const STILL_PLAYER_SCARE: f32 = 0.5;
+
+pub const SPAWN_ATTEMPTS: usize = 12;
+pub const SPAWN_CLEARANCE: f32 = 12.;
+pub const GROUP_SPREAD: f32 = 20.;
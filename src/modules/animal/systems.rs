@@ -0,0 +1,295 @@
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::hierarchy::BuildChildren;
+use bevy::log::warn;
+use bevy::math::{IVec2, Vec2};
+use bevy::prelude::{Added, Commands, ComputedVisibility, Entity, EventReader, GlobalTransform, Query, Res, ResMut, SpriteSheetBundle, TextureAtlasSprite, Time, Transform, Visibility, With, Without};
+use bevy::sprite::TextureAtlas;
+use bevy::utils::default;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::prelude::{LdtkLevel, LevelEvent};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use crate::modules::animal::components::{Activity, Animal, AnimalBundle, AnimalRegistry, AnimalSpawn, AnimalState, SpawnTable};
+use crate::modules::animal::consts::{ALIGNMENT_WEIGHT, ANIMAL_Z, CALM_SECONDS, COHESION_WEIGHT, FLEE_MULTIPLIER, GRAZE_MAX_SECONDS, GRAZE_MIN_SECONDS, GROUP_SPREAD, HERD_RADIUS, HOME_WEIGHT, ROAM_MAX_SECONDS, ROAM_MIN_SECONDS, ROAM_RADIUS, SEPARATION_RADIUS, SEPARATION_WEIGHT, SPAWN_ATTEMPTS, SPAWN_CLEARANCE, STILL_PLAYER_SCARE};
+use crate::modules::clock::components::WorldClock;
+use crate::modules::level::consts::{PROCEDURAL_FIELD, SPAWN_TABLE_FIELD};
+use crate::modules::level::fields::{bool_field, string_field};
+use crate::modules::living::components::Player;
+use crate::modules::physics::components::{Collider, SelfPhysical};
+use crate::modules::rng::components::{RngResource, RngStream};
+use crate::modules::worldgen::consts::CELL_SIZE;
+use crate::modules::worldgen::generator::{biome_at, Biome};
+
+// runs once the level is placed so the spots taken by its colliders are known
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_level_animals(
+    mut commands: Commands, mut level_events: EventReader<LevelEvent>, rng: Res<RngResource>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>, mut registry: ResMut<AnimalRegistry>,
+    levels: Query<(Entity, &Handle<LdtkLevel>, &GlobalTransform)>, ldtk_levels: Res<Assets<LdtkLevel>>,
+    colliders: Query<(&GlobalTransform, &Collider)>
+) {
+    for level_event in level_events.iter() {
+        let iid = match level_event {
+            LevelEvent::Transformed(iid) => iid,
+            _ => continue
+        };
+        let found = levels.iter().find_map(|(entity, handle, transform)| {
+            ldtk_levels.get(handle).filter(|ldtk_level| ldtk_level.level.iid == *iid)
+                .map(|ldtk_level| (entity, &ldtk_level.level, transform.translation().truncate()))
+        });
+        let (level_entity, level, origin) = match found {
+            Some(found) => found,
+            None => continue
+        };
+        let procedural = bool_field(&level.identifier, &level.field_instances, PROCEDURAL_FIELD) == Some(true);
+        let noise_seed = rng.derive_seed(RngStream::WORLDGEN.name());
+        let size = Vec2::new(level.px_wid as f32, level.px_hei as f32);
+        // positions are local to the level, y growing upwards like everything in bevy
+        let biome_of = |position: Vec2| biome_at(noise_seed, (
+            Vec2::new(level.world_x as f32 + position.x, (level.world_y + level.px_hei) as f32 - position.y) / CELL_SIZE
+        ).floor().as_ivec2());
+        let is_free = |position: Vec2| {
+            let world_position = origin + position;
+            position.x >= 0. && position.y >= 0. && position.x < size.x && position.y < size.y &&
+                !(procedural && biome_of(position) == Biome::WATER) &&
+                !colliders.iter().any(|(transform, collider)| {
+                    let min = transform.translation().truncate() + collider.0.offset - Vec2::splat(SPAWN_CLEARANCE);
+                    let max = min + collider.0.size + Vec2::splat(SPAWN_CLEARANCE * 2.);
+                    world_position.x >= min.x && world_position.y >= min.y && world_position.x < max.x && world_position.y < max.y
+                })
+        };
+
+        // a table named by the level wins, procedural levels otherwise share theirs out by biome
+        let table_name = string_field(&level.identifier, &level.field_instances, SPAWN_TABLE_FIELD)
+            .unwrap_or_else(|| level.identifier.clone());
+        let tables: Vec<(SpawnTable, Option<Biome>, u32)> = match registry.data.levels.get(&table_name) {
+            Some(table) => vec![(table.clone(), None, table.groups)],
+            None if procedural => {
+                let cells = IVec2::new(level.px_wid, level.px_hei) / CELL_SIZE as i32;
+                let mut counts = Vec::new();
+                for y in 0..cells.y {
+                    for x in 0..cells.x {
+                        counts.push(biome_of((Vec2::new(x as f32, y as f32) + Vec2::splat(0.5)) * CELL_SIZE));
+                    }
+                }
+                registry.data.biomes.iter().map(|(biome, table)| {
+                    let share = counts.iter().filter(|cell_biome| *cell_biome == biome).count() as f32 / counts.len().max(1) as f32;
+                    (table.clone(), Some(*biome), (table.groups as f32 * share).round() as u32)
+                }).collect()
+            }
+            None => continue
+        };
+
+        let mut level_rng = ChaCha12Rng::seed_from_u64(rng.derive_seed(&format!("animals:{}", level.iid)));
+        let mut children = Vec::new();
+        for (table, biome, groups) in tables {
+            for _ in 0..groups {
+                let entry = match table.entries.choose_weighted(&mut level_rng, |entry| entry.weight) {
+                    Ok(entry) => entry,
+                    Err(_) => break
+                };
+                let species = match registry.data.species.get(&entry.species) {
+                    Some(species) => species.clone(),
+                    None => {
+                        warn!("Spawn table {} refers to unknown species {}", table_name, entry.species);
+                        continue;
+                    }
+                };
+                let anchor = (0..SPAWN_ATTEMPTS)
+                    .map(|_| Vec2::new(level_rng.gen::<f32>() * size.x, level_rng.gen::<f32>() * size.y))
+                    .find(|position| is_free(*position) && biome.is_none_or(|biome| biome_of(*position) == biome));
+                let anchor = match anchor {
+                    Some(anchor) => anchor,
+                    None => continue
+                };
+                let texture_atlas = match registry.atlas(&entry.species, &asset_server, &mut texture_atlases) {
+                    Some(texture_atlas) => texture_atlas,
+                    None => continue
+                };
+                let count = level_rng.gen_range(entry.group.0..=entry.group.1.max(entry.group.0));
+                for member in 0..count {
+                    let position = (0..SPAWN_ATTEMPTS)
+                        .map(|attempt| if member == 0 && attempt == 0 {
+                            anchor
+                        } else {
+                            let angle = level_rng.gen_range(0. ..std::f32::consts::TAU);
+                            anchor + Vec2::new(angle.cos(), angle.sin()) * level_rng.gen_range(0. ..GROUP_SPREAD)
+                        })
+                        .find(|position| is_free(*position));
+                    let position = match position {
+                        Some(position) => position,
+                        None => continue
+                    };
+                    // a stable iid lets the world state remember where the animal went and whether it died
+                    let entity_instance = EntityInstance {
+                        identifier: entry.species.clone(),
+                        iid: format!("{}-animal-{}", level.iid, children.len()),
+                        width: species.frame_size.0 as i32,
+                        height: species.frame_size.1 as i32,
+                        px: IVec2::new(position.x as i32, level.px_hei - position.y as i32),
+                        ..default()
+                    };
+                    let mut animal = commands.spawn((
+                        SpriteSheetBundle {
+                            texture_atlas: texture_atlas.clone(),
+                            transform: Transform::from_xyz(position.x, position.y, ANIMAL_Z),
+                            ..default()
+                        },
+                        AnimalBundle::new(&entry.species, &species),
+                        entity_instance
+                    ));
+                    if let Some(collider) = species.collider() {
+                        animal.insert(collider);
+                    }
+                    children.push(animal.id());
+                }
+            }
+        }
+        commands.entity(level_entity).push_children(&children);
+    }
+}
+
+pub fn spawn_placed_animals(
+    mut commands: Commands, asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut registry: ResMut<AnimalRegistry>, spawns: Query<(Entity, &AnimalSpawn, &EntityInstance), Added<AnimalSpawn>>
+) {
+    for (entity, spawn, entity_instance) in spawns.iter() {
+        let name = match &spawn.species {
+            Some(name) => name,
+            None => {
+                warn!("Animal {} has no species", entity_instance.iid);
+                continue;
+            }
+        };
+        let (species, texture_atlas) = match (
+            registry.data.species.get(name).cloned(), registry.atlas(name, &asset_server, &mut texture_atlases)
+        ) {
+            (Some(species), Some(texture_atlas)) => (species, texture_atlas),
+            _ => {
+                warn!("Animal {} has unknown species {}", entity_instance.iid, name);
+                continue;
+            }
+        };
+        let mut animal = commands.entity(entity);
+        animal.insert((
+            AnimalBundle::new(name, &species), texture_atlas, TextureAtlasSprite {..default()},
+            Visibility::VISIBLE, ComputedVisibility::default()
+        ));
+        if let Some(collider) = species.collider() {
+            animal.insert(collider);
+        }
+    }
+}
+
+pub fn update_animal_activity(clock: Res<WorldClock>, mut animals: Query<&mut Animal>) {
+    for mut animal in animals.iter_mut() {
+        let active = match animal.activity {
+            Activity::ALWAYS => true,
+            Activity::DIURNAL => !clock.is_night(),
+            Activity::NOCTURNAL => clock.is_night(),
+            Activity::CREPUSCULAR => clock.is_twilight(),
+        };
+        if animal.active != active {
+            animal.active = active;
+        }
+    }
+}
+
+pub fn update_animals(
+    time: Res<Time>, mut rng: ResMut<RngResource>, players: Query<(&GlobalTransform, &SelfPhysical), With<Player>>,
+    mut animals: Query<(Entity, &mut Animal, &mut SelfPhysical, &Transform, &GlobalTransform), Without<Player>>
+) {
+    let rng = rng.stream(RngStream::AI);
+    let player = players.get_single().ok().map(|(transform, self_physical)| {
+        let moving = self_physical.direction.truncate() != Vec2::ZERO;
+        let scare = if moving { self_physical.multiplier } else { STILL_PLAYER_SCARE };
+        (transform.translation().truncate(), scare)
+    });
+    // last frame's herd, so every animal reacts to the same picture
+    let herd: Vec<(Entity, String, Vec2, Vec2, bool)> = animals.iter()
+        .map(|(entity, animal, _, _, transform)| (
+            entity, animal.species.clone(), transform.translation().truncate(), animal.heading, animal.state == AnimalState::FLEE
+        ))
+        .collect();
+
+    for (entity, mut animal, mut self_physical, transform, global_transform) in animals.iter_mut() {
+        let position = global_transform.translation().truncate();
+        let home = *animal.home.get_or_insert(transform.translation.truncate()) - transform.translation.truncate() + position;
+        let neighbours: Vec<_> = herd.iter()
+            .filter(|(other, species, other_position, _, _)| {
+                *other != entity && *species == animal.species && other_position.distance(position) < HERD_RADIUS
+            })
+            .collect();
+
+        let mut separation = Vec2::ZERO;
+        for (_, _, other_position, _, _) in neighbours.iter() {
+            let offset = position - *other_position;
+            if offset.length() < SEPARATION_RADIUS {
+                separation += offset.normalize_or_zero() * (1. - offset.length() / SEPARATION_RADIUS);
+            }
+        }
+
+        let threat = player
+            .map(|(player_position, scare)| (position - player_position, animal.flee_distance * scare))
+            .filter(|(offset, distance)| offset.length() < *distance)
+            .map(|(offset, _)| offset.normalize_or_zero());
+        let panic = if animal.herd {
+            neighbours.iter()
+                .filter(|(_, _, _, _, fleeing)| *fleeing)
+                .map(|(_, _, _, heading, _)| *heading)
+                .reduce(|sum, heading| sum + heading)
+                .map(|heading| heading.normalize_or_zero())
+        } else {
+            None
+        };
+
+        let fleeing = animal.state == AnimalState::FLEE;
+        let calm = animal.timer.tick(time.delta()).finished();
+        let direction = if let Some(away) = threat.or(panic.filter(|heading| *heading != Vec2::ZERO)) {
+            animal.enter(AnimalState::FLEE, (away + separation * SEPARATION_WEIGHT).normalize_or_zero(), CALM_SECONDS);
+            animal.heading
+        } else if fleeing && !calm {
+            animal.heading
+        } else if !animal.active {
+            if animal.state != AnimalState::REST {
+                animal.enter(AnimalState::REST, Vec2::ZERO, 0.);
+            }
+            Vec2::ZERO
+        } else if animal.state == AnimalState::ROAM && !calm {
+            let mut steering = animal.heading;
+            if !neighbours.is_empty() {
+                let center = neighbours.iter().map(|(_, _, other_position, _, _)| *other_position).sum::<Vec2>()
+                    / neighbours.len() as f32;
+                let alignment = neighbours.iter().map(|(_, _, _, heading, _)| *heading).sum::<Vec2>();
+                steering += (center - position).normalize_or_zero() * COHESION_WEIGHT
+                    + alignment.normalize_or_zero() * ALIGNMENT_WEIGHT;
+            }
+            if home.distance(position) > ROAM_RADIUS {
+                steering += (home - position).normalize_or_zero() * HOME_WEIGHT;
+            }
+            (steering + separation * SEPARATION_WEIGHT).normalize_or_zero()
+        } else if animal.state == AnimalState::GRAZE && !calm {
+            // grazing animals only shuffle apart when they bump into each other
+            separation.normalize_or_zero()
+        } else if animal.state == AnimalState::GRAZE {
+            let angle = rng.gen_range(0. ..std::f32::consts::TAU);
+            animal.enter(AnimalState::ROAM, Vec2::new(angle.cos(), angle.sin()), rng.gen_range(ROAM_MIN_SECONDS..ROAM_MAX_SECONDS));
+            animal.heading
+        } else {
+            animal.enter(AnimalState::GRAZE, Vec2::ZERO, rng.gen_range(GRAZE_MIN_SECONDS..GRAZE_MAX_SECONDS));
+            Vec2::ZERO
+        };
+
+        if animal.state == AnimalState::ROAM && direction != Vec2::ZERO {
+            animal.heading = direction;
+        }
+        let multiplier = if animal.state == AnimalState::FLEE { FLEE_MULTIPLIER } else { 1. };
+        let direction = direction.extend(0.);
+        if self_physical.direction != direction || self_physical.multiplier != multiplier {
+            self_physical.direction = direction;
+            self_physical.multiplier = multiplier;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::math::{IVec2, Vec2};
+use bevy::prelude::Entity;
+use bevy::sprite::TextureAtlas;
+use bevy::utils::default;
+use serde::{Deserialize, Serialize};
+use crate::modules::building::consts::STRUCTURE_DATA_PATH;
+use crate::modules::building::validation::validate_structure;
+use crate::modules::crafting::components::RecipeBook;
+use crate::modules::inventory::components::ItemDatabase;
+use crate::modules::level::consts::LEVEL_GRID_SIZE;
+use crate::modules::physics::components::{Collider, TransformZone};
+use crate::modules::storage::files::read_ron;
+use crate::modules::tech::components::{Progress, TechTree};
+
+#[derive(Clone, Deserialize)]
+pub struct StructureDefinition {
+    pub name: String,
+    // footprint in level cells
+    pub cells: (i32, i32),
+    pub cost: Vec<(String, u32)>,
+    // a single sprite as large as the footprint
+    #[serde(default)]
+    pub texture: Option<String>,
+    // workstations look and collide like the workstation they become
+    #[serde(default)]
+    pub workstation: Option<String>,
+    // (width, height) at the structure's base
+    #[serde(default)]
+    pub collider: Option<(f32, f32)>,
+    // locked structures can be built once a discovery unlocks them
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl StructureDefinition {
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.cells.0 as f32, self.cells.1 as f32) * LEVEL_GRID_SIZE
+    }
+
+    pub fn collider(&self, book: &RecipeBook) -> Option<Collider> {
+        if let Some(workstation) = &self.workstation {
+            return book.workstation(workstation)?.collider();
+        }
+        let (width, height) = self.collider?;
+        Some(Collider(TransformZone {
+            size: Vec2::new(width, height),
+            offset: Vec2::new(-width / 2., -self.size().y / 2.),
+        }))
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct StructureData {
+    pub structures: HashMap<String, StructureDefinition>,
+}
+
+#[derive(Default, Resource)]
+pub struct StructureCatalog {
+    pub data: StructureData,
+    pub atlases: HashMap<String, Handle<TextureAtlas>>,
+}
+
+impl StructureCatalog {
+    // structures that don't match the items and workstations are left out
+    pub fn load(items: &ItemDatabase, book: &RecipeBook) -> Self {
+        let mut data: StructureData = read_ron(STRUCTURE_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load structures from {}", STRUCTURE_DATA_PATH);
+            StructureData {..default()}
+        });
+        data.structures.retain(|name, structure| {
+            let errors = validate_structure(structure, items, book);
+            for error in errors.iter() {
+                warn!("Structure {} {}", name, error);
+            }
+            errors.is_empty()
+        });
+        StructureCatalog { data, ..default() }
+    }
+
+    pub fn get(&self, structure: &str) -> Option<&StructureDefinition> {
+        self.data.structures.get(structure)
+    }
+
+    // the structures the player can build, in menu order
+    pub fn available(&self, progress: &Progress, tree: &TechTree) -> Vec<(&String, &StructureDefinition)> {
+        let mut structures: Vec<_> = self.data.structures.iter()
+            .filter(|(name, structure)| !structure.locked || progress.unlocks_structure(tree, name))
+            .collect();
+        structures.sort_by(|(a_name, a), (b_name, b)| a.name.cmp(&b.name).then(a_name.cmp(b_name)));
+        structures
+    }
+
+    // (atlas, index) of the structure's sprite
+    pub fn sprite(
+        &mut self, structure: &str, book: &mut RecipeBook, asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>
+    ) -> Option<(Handle<TextureAtlas>, usize)> {
+        let definition = self.data.structures.get(structure)?;
+        if let Some(workstation) = &definition.workstation {
+            let frame = book.workstation(workstation)?.frame(false);
+            return Some((book.atlas(asset_server, texture_atlases), frame));
+        }
+        let texture = definition.texture.as_ref()?;
+        let size = definition.size();
+        Some((self.atlases.entry(structure.to_string()).or_insert_with(|| {
+            texture_atlases.add(TextureAtlas::from_grid(asset_server.load(texture), size, 1, 1, None, None))
+        }).clone(), 0))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PlacedStructure {
+    pub structure: String,
+    pub level_iid: String,
+    // lower left cell of the footprint, counted from the level's lower left corner
+    pub cell: (i32, i32),
+}
+
+impl PlacedStructure {
+    // center of the footprint relative to the level
+    pub fn position(&self, definition: &StructureDefinition) -> Vec2 {
+        IVec2::new(self.cell.0, self.cell.1).as_vec2() * LEVEL_GRID_SIZE + definition.size() / 2.
+    }
+}
+
+// everything the player built, respawned with the level it stands in
+#[derive(Clone, Default, Resource, Serialize, Deserialize)]
+pub struct PlacedStructures {
+    pub structures: Vec<PlacedStructure>,
+}
+
+#[derive(Component)]
+pub struct Structure {
+    pub structure: String,
+}
+
+pub struct StructurePlaced {
+    pub entity: Entity,
+    pub structure: String,
+}
+
+#[derive(Default, Resource)]
+pub struct BuildMode {
+    pub active: bool,
+    // index into the available structures
+    pub selected: usize,
+    // where the selected structure would go, if it can go there
+    pub placement: Option<PlacedStructure>,
+    // why the structure can't be placed there
+    pub problem: Option<&'static str>,
+}
+
+#[derive(Default, Component)]
+pub struct BuildGhost;
+
+#[derive(Default, Component)]
+pub struct BuildMenuText;
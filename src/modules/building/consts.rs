@@ -0,0 +1,17 @@
+use bevy::prelude::KeyCode;
+
+pub const STRUCTURE_DATA_PATH: &str = "assets/data/structures.ron";
+
+pub const BUILD_KEY: KeyCode = KeyCode::B;
+pub const BUILD_MENU: &str = "build";
+pub const NEXT_STRUCTURE_KEY: KeyCode = KeyCode::Tab;
+pub const PLACE_KEY: KeyCode = KeyCode::F;
+
+pub const STRUCTURE_Z: f32 = 3.;
+// drawn over everything it previews
+pub const GHOST_Z: f32 = 100.;
+pub const GHOST_VALID_TINT: (f32, f32, f32, f32) = (0.6, 1., 0.6, 0.6);
+pub const GHOST_INVALID_TINT: (f32, f32, f32, f32) = (1., 0.4, 0.4, 0.6);
+// gap between the player's feet and the structure it places, half a cell keeps snapping from overlapping them
+pub const BUILD_DISTANCE: f32 = 8.;
+pub const PLACE_SOUND: &str = "gather/wood";
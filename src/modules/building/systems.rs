@@ -0,0 +1,347 @@
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::hierarchy::BuildChildren;
+use bevy::input::Input;
+use bevy::math::Vec2;
+use bevy::prelude::{Color, Commands, Entity, EventReader, EventWriter, GlobalTransform, KeyCode, Query, Res, ResMut, SpriteSheetBundle, Text, TextureAtlasSprite, Transform, Visibility, With, Without, World};
+use bevy::sprite::TextureAtlas;
+use bevy::utils::default;
+use bevy_ecs_ldtk::prelude::{IntGridCell, LdtkLevel, LevelEvent};
+use crate::modules::building::components::{BuildGhost, BuildMenuText, BuildMode, PlacedStructure, PlacedStructures, Structure, StructureCatalog, StructurePlaced};
+use crate::modules::building::consts::{BUILD_DISTANCE, BUILD_KEY, BUILD_MENU, GHOST_INVALID_TINT, GHOST_VALID_TINT, GHOST_Z, NEXT_STRUCTURE_KEY, PLACE_KEY, PLACE_SOUND, STRUCTURE_Z};
+use crate::modules::crafting::components::{CraftingMenu, RecipeBook, WorkstationBundle};
+use crate::modules::crafting::consts::{CLOSE_MENU_KEY, CRAFTING_MENU};
+use crate::modules::input::components::OpenMenus;
+use crate::modules::inventory::components::{Inventory, InventoryChanged, ItemDatabase};
+use crate::modules::level::components::LevelTransition;
+use crate::modules::level::consts::LEVEL_GRID_SIZE;
+use crate::modules::living::components::Player;
+use crate::modules::navigation::consts::COLLISION_INT_GRID_VALUE;
+use crate::modules::physics::components::{Collider, TransformZone};
+use crate::modules::physics::sprite_change::components::{MultipleSided, SpriteZone};
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
+use crate::modules::tech::components::{Progress, TechTree};
+
+// runs after the log plugin is built, the catalog warns about structures it drops
+pub fn load_structures(world: &mut World) {
+    let structures = StructureCatalog::load(world.resource::<ItemDatabase>(), world.resource::<RecipeBook>());
+    world.insert_resource(structures);
+}
+
+pub fn spawn_structure(
+    commands: &mut Commands, catalog: &mut StructureCatalog, book: &mut RecipeBook, asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>, placed: &PlacedStructure, level: Entity
+) -> Option<Entity> {
+    let definition = catalog.get(&placed.structure)?.clone();
+    let (texture_atlas, index) = catalog.sprite(&placed.structure, book, asset_server, texture_atlases)?;
+    let position = placed.position(&definition);
+    let size = definition.size();
+    let mut structure = commands.spawn((
+        SpriteSheetBundle {
+            texture_atlas,
+            sprite: TextureAtlasSprite::new(index),
+            transform: Transform::from_xyz(position.x, position.y, STRUCTURE_Z),
+            ..default()
+        },
+        Structure { structure: placed.structure.clone() },
+        SpriteZone(TransformZone { size, offset: -size / 2. })
+    ));
+    if let Some(collider) = definition.collider(book) {
+        structure.insert(collider);
+    }
+    if let Some((kind, workstation)) = definition.workstation.as_ref()
+        .and_then(|kind| book.workstation(kind).map(|workstation| (kind, workstation))) {
+        structure.insert(WorkstationBundle::new(kind, workstation));
+    }
+    let entity = structure.id();
+    commands.entity(level).add_child(entity);
+    Some(entity)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_level_structures(
+    mut commands: Commands, mut level_events: EventReader<LevelEvent>, placed: Res<PlacedStructures>,
+    mut catalog: ResMut<StructureCatalog>, mut book: ResMut<RecipeBook>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    levels: Query<(Entity, &Handle<LdtkLevel>)>, ldtk_levels: Res<Assets<LdtkLevel>>
+) {
+    for level_event in level_events.iter() {
+        let iid = match level_event {
+            LevelEvent::Transformed(iid) => iid,
+            _ => continue
+        };
+        let level = levels.iter()
+            .find(|(_, handle)| ldtk_levels.get(handle).is_some_and(|ldtk_level| ldtk_level.level.iid == *iid))
+            .map(|(entity, _)| entity);
+        let level = match level {
+            Some(level) => level,
+            None => continue
+        };
+        for structure in placed.structures.iter().filter(|structure| structure.level_iid == *iid) {
+            spawn_structure(&mut commands, &mut catalog, &mut book, &asset_server, &mut texture_atlases, structure, level);
+        }
+    }
+}
+
+// building and crafting share the corner of the screen, so opening one closes the other
+pub fn toggle_build_mode(
+    keyboard_input: Res<Input<KeyCode>>, mut build_mode: ResMut<BuildMode>, mut crafting_menu: ResMut<CraftingMenu>,
+    mut open_menus: ResMut<OpenMenus>, transition: Res<LevelTransition>
+) {
+    let mut active = build_mode.active;
+    if keyboard_input.just_pressed(BUILD_KEY) {
+        active = !active;
+        if active && crafting_menu.open {
+            crafting_menu.open = false;
+            open_menus.set(CRAFTING_MENU, false);
+        }
+    } else if crafting_menu.open {
+        active = false;
+    }
+    if keyboard_input.just_pressed(CLOSE_MENU_KEY) || transition.is_active() {
+        active = false;
+    }
+    if build_mode.active != active {
+        build_mode.active = active;
+        open_menus.set(BUILD_MENU, active);
+    }
+    if active && keyboard_input.just_pressed(NEXT_STRUCTURE_KEY) {
+        build_mode.selected += 1;
+    }
+}
+
+// snaps the selected structure to the level grid in front of the player
+#[allow(clippy::too_many_arguments)]
+pub fn update_build_placement(
+    mut build_mode: ResMut<BuildMode>, catalog: Res<StructureCatalog>, book: Res<RecipeBook>,
+    progress: Res<Progress>, tree: Res<TechTree>,
+    players: Query<(&GlobalTransform, &Collider, &MultipleSided, &Inventory), With<Player>>,
+    levels: Query<(&Handle<LdtkLevel>, &GlobalTransform)>, ldtk_levels: Res<Assets<LdtkLevel>>,
+    colliders: Query<(&GlobalTransform, &Collider)>, int_grid_cells: Query<(&IntGridCell, &GlobalTransform)>
+) {
+    if !build_mode.active {
+        if build_mode.placement.is_some() {
+            build_mode.placement = None;
+        }
+        return;
+    }
+    let available = catalog.available(&progress, &tree);
+    let (player_transform, player_collider, multiple_sided, inventory) = match (available.is_empty(), players.get_single()) {
+        (false, Ok(player)) => player,
+        _ => {
+            build_mode.placement = None;
+            return;
+        }
+    };
+    let selected = build_mode.selected % available.len();
+    let (name, definition) = available[selected];
+    let size = definition.size();
+    let facing = multiple_sided.side.direction();
+    let feet = player_transform.translation().truncate() + player_collider.0.offset + player_collider.0.size / 2.;
+    let reach = ((player_collider.0.size + size) * facing.abs()).max_element() / 2. + BUILD_DISTANCE;
+    let target = feet + facing * reach;
+
+    let level = levels.iter().find_map(|(handle, transform)| {
+        let level = &ldtk_levels.get(handle)?.level;
+        let origin = transform.translation().truncate();
+        let local = target - origin;
+        let inside = local.x >= 0. && local.y >= 0. && local.x < level.px_wid as f32 && local.y < level.px_hei as f32;
+        inside.then_some((level, origin, local))
+    });
+    let (level, origin, local) = match level {
+        Some(level) => level,
+        None => {
+            build_mode.placement = None;
+            return;
+        }
+    };
+    let cell = ((local - size / 2.) / LEVEL_GRID_SIZE).round().as_ivec2();
+    let placement = PlacedStructure { structure: name.clone(), level_iid: level.iid.clone(), cell: (cell.x, cell.y) };
+    let center = origin + placement.position(definition);
+
+    let footprint = definition.collider(&book).map_or(
+        TransformZone { size, offset: -size / 2. }, |collider| collider.0
+    );
+    let min = center + footprint.offset;
+    let max = min + footprint.size;
+    let overlaps = |other_min: Vec2, other_max: Vec2| {
+        min.x < other_max.x && max.x > other_min.x && min.y < other_max.y && max.y > other_min.y
+    };
+    let half_cell = Vec2::splat(LEVEL_GRID_SIZE / 2.);
+    let level_max = Vec2::new(level.px_wid as f32, level.px_hei as f32);
+    let local_min = center - origin - size / 2.;
+
+    build_mode.problem = if local_min.x < 0. || local_min.y < 0. || (local_min + size).cmpgt(level_max).any() {
+        Some("Too close to the edge")
+    } else if colliders.iter().any(|(transform, collider)| {
+        let other_min = transform.translation().truncate() + collider.0.offset;
+        overlaps(other_min, other_min + collider.0.size)
+    }) || int_grid_cells.iter().any(|(int_grid_cell, transform)| {
+        let cell_center = transform.translation().truncate();
+        int_grid_cell.value == COLLISION_INT_GRID_VALUE && overlaps(cell_center - half_cell, cell_center + half_cell)
+    }) {
+        Some("Something is in the way")
+    } else if !definition.cost.iter().all(|(item, count)| inventory.has(item, *count)) {
+        Some("Missing materials")
+    } else {
+        None
+    };
+    build_mode.placement = Some(placement);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_build_ghost(
+    mut commands: Commands, build_mode: Res<BuildMode>, mut catalog: ResMut<StructureCatalog>,
+    mut book: ResMut<RecipeBook>, asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    levels: Query<(&Handle<LdtkLevel>, &GlobalTransform)>, ldtk_levels: Res<Assets<LdtkLevel>>,
+    mut ghosts: Query<(&mut Handle<TextureAtlas>, &mut TextureAtlasSprite, &mut Transform, &mut Visibility), With<BuildGhost>>
+) {
+    let shown = build_mode.placement.as_ref().and_then(|placement| {
+        let definition = catalog.get(&placement.structure)?.clone();
+        let origin = levels.iter()
+            .find(|(handle, _)| ldtk_levels.get(handle).is_some_and(|ldtk_level| ldtk_level.level.iid == placement.level_iid))
+            .map(|(_, transform)| transform.translation().truncate())?;
+        let sprite = catalog.sprite(&placement.structure, &mut book, &asset_server, &mut texture_atlases)?;
+        Some((sprite, origin + placement.position(&definition)))
+    });
+    let ((texture_atlas, index), position) = match (shown, ghosts.get_single_mut()) {
+        (Some(shown), _) => shown,
+        (None, Ok((_, _, _, mut visibility))) => {
+            if visibility.is_visible {
+                visibility.is_visible = false;
+            }
+            return;
+        }
+        (None, Err(_)) => return
+    };
+    let tint = if build_mode.problem.is_none() { GHOST_VALID_TINT } else { GHOST_INVALID_TINT };
+    let color = Color::rgba(tint.0, tint.1, tint.2, tint.3);
+    let transform = Transform::from_xyz(position.x, position.y, GHOST_Z);
+    match ghosts.get_single_mut() {
+        Ok((mut ghost_atlas, mut sprite, mut ghost_transform, mut visibility)) => {
+            if *ghost_atlas != texture_atlas {
+                *ghost_atlas = texture_atlas;
+            }
+            if sprite.index != index || sprite.color != color {
+                sprite.index = index;
+                sprite.color = color;
+            }
+            if *ghost_transform != transform {
+                *ghost_transform = transform;
+            }
+            if !visibility.is_visible {
+                visibility.is_visible = true;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                SpriteSheetBundle {
+                    texture_atlas,
+                    sprite: TextureAtlasSprite { index, color, ..default() },
+                    transform,
+                    ..default()
+                },
+                BuildGhost
+            ));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn place_structure(
+    mut commands: Commands, keyboard_input: Res<Input<KeyCode>>, build_mode: Res<BuildMode>,
+    mut catalog: ResMut<StructureCatalog>, mut book: ResMut<RecipeBook>, mut placed: ResMut<PlacedStructures>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut players: Query<(Entity, &mut Inventory), With<Player>>,
+    levels: Query<(Entity, &Handle<LdtkLevel>), Without<Player>>, ldtk_levels: Res<Assets<LdtkLevel>>,
+    mut inventory_events: EventWriter<InventoryChanged>, mut placed_events: EventWriter<StructurePlaced>,
+    mut sound_events: EventWriter<SoundEvent>
+) {
+    if !build_mode.active || !keyboard_input.just_pressed(PLACE_KEY) || build_mode.problem.is_some() {
+        return;
+    }
+    let placement = match &build_mode.placement {
+        Some(placement) => placement,
+        None => return
+    };
+    let (player, mut inventory) = match players.get_single_mut() {
+        Ok(player) => player,
+        _ => return
+    };
+    let level = levels.iter()
+        .find(|(_, handle)| ldtk_levels.get(handle).is_some_and(|ldtk_level| ldtk_level.level.iid == placement.level_iid))
+        .map(|(entity, _)| entity);
+    let (level, cost) = match (level, catalog.get(&placement.structure)) {
+        (Some(level), Some(definition)) => (level, definition.cost.clone()),
+        _ => return
+    };
+    if !cost.iter().all(|(item, count)| inventory.has(item, *count)) {
+        return;
+    }
+    let entity = match spawn_structure(
+        &mut commands, &mut catalog, &mut book, &asset_server, &mut texture_atlases, placement, level
+    ) {
+        Some(entity) => entity,
+        None => return
+    };
+    for (item, count) in cost {
+        inventory.remove(&item, count);
+        inventory_events.send(InventoryChanged { entity: player, item, delta: -(count as i64) });
+    }
+    placed.structures.push(placement.clone());
+    placed_events.send(StructurePlaced { entity, structure: placement.structure.clone() });
+    sound_events.send(SoundEvent {
+        path: PLACE_SOUND.to_string(),
+        sound_type: SoundType::RANDOM,
+        file: String::new(),
+        channel: SoundChannel::SFX,
+        emitter: Some(entity),
+    });
+}
+
+pub fn update_build_menu(
+    build_mode: Res<BuildMode>, catalog: Res<StructureCatalog>, items: Res<ItemDatabase>,
+    progress: Res<Progress>, tree: Res<TechTree>,
+    mut menu_texts: Query<(&mut Text, &mut Visibility), With<BuildMenuText>>
+) {
+    let available = catalog.available(&progress, &tree);
+    let value = match available.get(build_mode.selected % available.len().max(1)) {
+        Some((_, definition)) => {
+            let cost: Vec<_> = definition.cost.iter()
+                .map(|(item, count)| format!("{} {}", count, items.get(item).map_or(item.as_str(), |item| item.name.as_str())))
+                .collect();
+            let action = match (&build_mode.placement, build_mode.problem) {
+                (None, _) => "Nowhere to build".to_string(),
+                (Some(_), Some(problem)) => problem.to_string(),
+                (Some(_), None) => format!("[{:?}] Place", PLACE_KEY),
+            };
+            format!("Build {} - {}\n[{:?}] Next  {}", definition.name, cost.join(", "), NEXT_STRUCTURE_KEY, action)
+        }
+        None => "Nothing to build yet".to_string()
+    };
+    for (mut text, mut visibility) in menu_texts.iter_mut() {
+        if visibility.is_visible != build_mode.active {
+            visibility.is_visible = build_mode.active;
+        }
+        if build_mode.active && text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, StartupStage};
+    use crate::modules::testing::logs::capture_warnings;
+    use super::*;
+
+    #[test]
+    fn loading_the_catalog_warns_about_dropped_structures() {
+        let mut app = App::new();
+        app.insert_resource(ItemDatabase::default())
+            .insert_resource(RecipeBook::default())
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_structures);
+        let warnings = capture_warnings(|| app.update());
+        assert!(warnings.contains("Structure "), "{}", warnings);
+        assert!(app.world.contains_resource::<StructureCatalog>());
+    }
+}
@@ -0,0 +1,30 @@
+use crate::modules::building::components::StructureDefinition;
+use crate::modules::crafting::components::RecipeBook;
+use crate::modules::inventory::components::ItemDatabase;
+
+pub fn validate_structure(structure: &StructureDefinition, items: &ItemDatabase, book: &RecipeBook) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (item, count) in structure.cost.iter() {
+        if items.get(item).is_none() {
+            errors.push(format!("costs unknown item {}", item));
+        }
+        if *count == 0 {
+            errors.push(format!("costs no {}", item));
+        }
+    }
+    if structure.cells.0 <= 0 || structure.cells.1 <= 0 {
+        errors.push(format!("covers {}x{} cells", structure.cells.0, structure.cells.1));
+    }
+    match (&structure.texture, &structure.workstation) {
+        (Some(_), Some(_)) => errors.push("has both a texture and a workstation".to_string()),
+        (None, None) => errors.push("has neither a texture nor a workstation".to_string()),
+        (None, Some(workstation)) if book.workstation(workstation).is_none() => {
+            errors.push(format!("becomes unknown workstation {}", workstation));
+        }
+        _ => {}
+    }
+    if structure.workstation.is_some() && structure.collider.is_some() {
+        errors.push("has a collider of its own besides its workstation's".to_string());
+    }
+    errors
+}
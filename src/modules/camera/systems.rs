@@ -5,12 +5,9 @@ pub fn camera_follow(
     camera_targets: Query<&mut Transform, (With<CameraTarget>, Changed<Transform>)>,
     mut cameras: Query<&mut Transform, (With<Camera>, Without<CameraTarget>)>
 ) {
-    match camera_targets.get_single() {
-        Ok(target) => {
-            let mut camera = cameras.get_single_mut().unwrap();
-            camera.translation.x = target.translation.x;
-            camera.translation.y = target.translation.y;
-        }
-        _ => {}
-    };
+    if let Ok(target) = camera_targets.get_single() {
+        let mut camera = cameras.get_single_mut().unwrap();
+        camera.translation.x = target.translation.x;
+        camera.translation.y = target.translation.y;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,154 @@
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+use crate::modules::clock::consts::{CLOCK_DATA_PATH, DEFAULT_DAWN_HOUR, DEFAULT_DAY_SECONDS, DEFAULT_DUSK_HOUR, DEFAULT_TWILIGHT_HOURS, HOURS_PER_DAY, START_HOUR};
+use crate::modules::clock::validation::validate_settings;
+use crate::modules::storage::files::read_ron;
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ClockSettings {
+    // real seconds a whole day takes
+    pub day_seconds: f32,
+    pub dawn_hour: f32,
+    pub dusk_hour: f32,
+    // how long around dawn and dusk counts as twilight
+    pub twilight_hours: f32,
+    // (hour, rgb) the world is tinted with, blended in between and wrapping around midnight
+    pub tints: Vec<(f32, (f32, f32, f32))>,
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        ClockSettings {
+            day_seconds: DEFAULT_DAY_SECONDS,
+            dawn_hour: DEFAULT_DAWN_HOUR,
+            dusk_hour: DEFAULT_DUSK_HOUR,
+            twilight_hours: DEFAULT_TWILIGHT_HOURS,
+            tints: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockTime {
+    // days since the game started
+    pub day: u32,
+    pub hour: f32,
+}
+
+impl Default for ClockTime {
+    fn default() -> Self {
+        ClockTime { day: 0, hour: START_HOUR }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClockEvent { DAWN(u32), DUSK(u32), MIDNIGHT(u32) }
+
+#[derive(Default, Resource)]
+pub struct WorldClock {
+    pub settings: ClockSettings,
+    pub time: ClockTime,
+}
+
+impl WorldClock {
+    // settings that don't make a day fall back to the default ones
+    pub fn load() -> Self {
+        let settings: ClockSettings = read_ron(CLOCK_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load the clock from {}", CLOCK_DATA_PATH);
+            ClockSettings::default()
+        });
+        let errors = validate_settings(&settings);
+        for error in errors.iter() {
+            warn!("Clock {}", error);
+        }
+        let mut settings = if errors.is_empty() { settings } else { ClockSettings::default() };
+        settings.tints.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        WorldClock { settings, time: ClockTime::default() }
+    }
+
+    pub fn restore(&mut self, time: ClockTime) {
+        self.time = ClockTime { day: time.day, hour: time.hour.rem_euclid(HOURS_PER_DAY) };
+    }
+
+    pub fn hour(&self) -> f32 {
+        self.time.hour
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.time.hour < self.settings.dawn_hour || self.time.hour >= self.settings.dusk_hour
+    }
+
+    pub fn is_twilight(&self) -> bool {
+        let half = self.settings.twilight_hours / 2.;
+        [self.settings.dawn_hour, self.settings.dusk_hour].iter()
+            .any(|mark| (self.time.hour - mark).abs() <= half)
+    }
+
+    // moves time on and tells which marks it passed, in order
+    pub fn advance(&mut self, seconds: f32) -> Vec<ClockEvent> {
+        let mut events = Vec::new();
+        let mut left = seconds * HOURS_PER_DAY / self.settings.day_seconds;
+        while left > 0. {
+            let hour = self.time.hour;
+            let until_midnight = HOURS_PER_DAY - hour;
+            let next = if left < until_midnight { hour + left } else { HOURS_PER_DAY };
+            left = (left - until_midnight).max(0.);
+            let marks = [
+                (self.settings.dawn_hour, ClockEvent::DAWN as fn(u32) -> ClockEvent), (self.settings.dusk_hour, ClockEvent::DUSK)
+            ];
+            for (mark, event) in marks {
+                if hour < mark && next >= mark {
+                    events.push(event(self.time.day));
+                }
+            }
+            if next >= HOURS_PER_DAY {
+                self.time.day += 1;
+                self.time.hour = 0.;
+                events.push(ClockEvent::MIDNIGHT(self.time.day));
+            } else {
+                self.time.hour = next;
+            }
+        }
+        events
+    }
+
+    pub fn tint(&self) -> Color {
+        let tints = &self.settings.tints;
+        let (first, last) = match (tints.first(), tints.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::WHITE
+        };
+        let hour = self.time.hour;
+        let ((from_hour, from), (to_hour, to)) = match tints.iter().position(|(keyframe, _)| *keyframe > hour) {
+            Some(index) if index > 0 => (tints[index - 1], tints[index]),
+            // before the first keyframe, coming from yesterday's last one
+            Some(_) => ((last.0 - HOURS_PER_DAY, last.1), *first),
+            // after the last keyframe, heading for tomorrow's first one
+            None => (*last, (first.0 + HOURS_PER_DAY, first.1)),
+        };
+        let span = to_hour - from_hour;
+        let blend = if span > 0. { ((hour - from_hour) / span).clamp(0., 1.) } else { 0. };
+        let mix = |a: f32, b: f32| a + (b - a) * blend;
+        Color::rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+// the world's colour without daylight, so other systems can keep setting colours of their own
+#[derive(Component)]
+pub struct TintBase(pub Color);
+
+#[derive(Resource)]
+pub struct Daylight {
+    pub tint: Color,
+}
+
+impl Default for Daylight {
+    fn default() -> Self {
+        Daylight { tint: Color::WHITE }
+    }
+}
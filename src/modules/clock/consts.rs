@@ -0,0 +1,13 @@
+pub const CLOCK_DATA_PATH: &str = "assets/data/clock.ron";
+
+pub const HOURS_PER_DAY: f32 = 24.;
+// new games and saves from before the clock start in the morning
+pub const START_HOUR: f32 = 8.;
+
+pub const DEFAULT_DAY_SECONDS: f32 = 600.;
+pub const DEFAULT_DAWN_HOUR: f32 = 6.;
+pub const DEFAULT_DUSK_HOUR: f32 = 19.;
+pub const DEFAULT_TWILIGHT_HOURS: f32 = 1.5;
+
+// tile layers are only recoloured once the tint moved this far
+pub const TINT_STEP: f32 = 0.01;
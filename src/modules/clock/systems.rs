@@ -0,0 +1,98 @@
+use bevy::prelude::{Color, Commands, Entity, EventWriter, Mut, Query, Res, ResMut, Sprite, TextureAtlasSprite, Time, With, Without};
+use bevy_ecs_tilemap::tiles::TileColor;
+use crate::modules::clock::components::{ClockEvent, Daylight, TintBase, WorldClock};
+use crate::modules::clock::consts::TINT_STEP;
+use crate::modules::input::components::OpenMenus;
+
+pub fn advance_clock(
+    time: Res<Time>, mut clock: ResMut<WorldClock>, open_menus: Res<OpenMenus>, mut clock_events: EventWriter<ClockEvent>
+) {
+    if open_menus.any() {
+        return;
+    }
+    clock_events.send_batch(clock.advance(time.delta_seconds()));
+}
+
+pub fn update_daylight(clock: Res<WorldClock>, mut daylight: ResMut<Daylight>) {
+    let tint = clock.tint();
+    let moved = [
+        tint.r() - daylight.tint.r(), tint.g() - daylight.tint.g(), tint.b() - daylight.tint.b()
+    ].iter().any(|difference| difference.abs() >= TINT_STEP);
+    if moved {
+        daylight.tint = tint;
+    }
+}
+
+fn tinted(color: Color, tint: Color) -> Color {
+    Color::rgba(color.r() * tint.r(), color.g() * tint.g(), color.b() * tint.b(), color.a())
+}
+
+// sprites get their own colours back before anything runs, and the daylight on top once everything has
+pub fn restore_sprite_colors(
+    mut sprites: Query<(&mut Sprite, &TintBase), Without<TextureAtlasSprite>>,
+    mut atlas_sprites: Query<(&mut TextureAtlasSprite, &TintBase), Without<Sprite>>
+) {
+    for (mut sprite, base) in sprites.iter_mut() {
+        if sprite.color != base.0 {
+            sprite.color = base.0;
+        }
+    }
+    for (mut sprite, base) in atlas_sprites.iter_mut() {
+        if sprite.color != base.0 {
+            sprite.color = base.0;
+        }
+    }
+}
+
+pub fn tint_sprites(
+    mut commands: Commands, daylight: Res<Daylight>,
+    mut sprites: Query<(Entity, &mut Sprite, Option<&mut TintBase>), Without<TextureAtlasSprite>>,
+    mut atlas_sprites: Query<(Entity, &mut TextureAtlasSprite, Option<&mut TintBase>), Without<Sprite>>
+) {
+    // nothing is tinted in plain daylight, so there is nothing to restore either
+    if daylight.tint == Color::WHITE {
+        let tinted = sprites.iter().filter(|(_, _, base)| base.is_some()).map(|(entity, _, _)| entity)
+            .chain(atlas_sprites.iter().filter(|(_, _, base)| base.is_some()).map(|(entity, _, _)| entity));
+        for entity in tinted {
+            commands.entity(entity).remove::<TintBase>();
+        }
+        return;
+    }
+    let mut tint = |entity: Entity, color: &mut Color, base: Option<Mut<TintBase>>| {
+        match base {
+            Some(mut base) => base.0 = *color,
+            None => {
+                commands.entity(entity).insert(TintBase(*color));
+            }
+        }
+        *color = tinted(*color, daylight.tint);
+    };
+    for (entity, mut sprite, base) in sprites.iter_mut() {
+        tint(entity, &mut sprite.color, base);
+    }
+    for (entity, mut sprite, base) in atlas_sprites.iter_mut() {
+        tint(entity, &mut sprite.color, base);
+    }
+}
+
+// tiles never change colour on their own, so they are only touched when the daylight does
+pub fn tint_tiles(
+    mut commands: Commands, daylight: Res<Daylight>,
+    mut tiles: Query<(Entity, &mut TileColor, Option<&TintBase>)>, untinted: Query<(), (With<TileColor>, Without<TintBase>)>
+) {
+    if !daylight.is_changed() && untinted.is_empty() {
+        return;
+    }
+    let changed = daylight.is_changed();
+    for (entity, mut tile_color, base) in tiles.iter_mut() {
+        let base = match base {
+            Some(_) if !changed => continue,
+            Some(base) => base.0,
+            None => {
+                commands.entity(entity).insert(TintBase(tile_color.0));
+                tile_color.0
+            }
+        };
+        tile_color.0 = tinted(base, daylight.tint);
+    }
+}
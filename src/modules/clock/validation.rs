@@ -0,0 +1,22 @@
+use crate::modules::clock::components::ClockSettings;
+use crate::modules::clock::consts::HOURS_PER_DAY;
+
+pub fn validate_settings(settings: &ClockSettings) -> Vec<String> {
+    let mut errors = Vec::new();
+    if settings.day_seconds.is_nan() || settings.day_seconds <= 0. {
+        errors.push(format!("lasts {} seconds a day", settings.day_seconds));
+    }
+    let in_day = |hour: f32| (0. ..HOURS_PER_DAY).contains(&hour);
+    if !in_day(settings.dawn_hour) || !in_day(settings.dusk_hour) || settings.dawn_hour >= settings.dusk_hour {
+        errors.push(format!("has dawn at {} and dusk at {}", settings.dawn_hour, settings.dusk_hour));
+    }
+    if settings.twilight_hours.is_nan() || settings.twilight_hours < 0. {
+        errors.push(format!("has {} hours of twilight", settings.twilight_hours));
+    }
+    for (hour, _) in settings.tints.iter() {
+        if !in_day(*hour) {
+            errors.push(format!("has a tint at hour {}", hour));
+        }
+    }
+    errors
+}
@@ -0,0 +1,239 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, TimerMode};
+use bevy::sprite::TextureAtlas;
+use bevy::time::Timer;
+use bevy::utils::default;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+use serde::{Deserialize, Serialize};
+use crate::modules::crafting::consts::{CRAFTING_DATA_PATH, WORKSTATION_SHEET_COLUMNS, WORKSTATION_SHEET_ROWS, WORKSTATION_SPRITES_PATH, WORKSTATION_SPRITE_SIZE};
+use crate::modules::crafting::validation::{validate_recipe, validate_workstation};
+use crate::modules::interaction::components::Interactable;
+use crate::modules::inventory::components::ItemDatabase;
+use crate::modules::level::consts::STATION_FIELD;
+use crate::modules::level::fields::entity_string;
+use crate::modules::physics::components::{Collider, TransformZone};
+use crate::modules::physics::sprite_change::components::SpriteZone;
+use crate::modules::storage::files::read_ron;
+
+#[derive(Clone, Deserialize)]
+pub struct WorkstationDefinition {
+    pub name: String,
+    // row of the workstation in the sprite sheet
+    pub sprite: usize,
+    // (width, height) at the workstation's base
+    #[serde(default)]
+    pub collider: Option<(f32, f32)>,
+}
+
+impl WorkstationDefinition {
+    pub fn collider(&self) -> Option<Collider> {
+        let (width, height) = self.collider?;
+        Some(Collider(TransformZone {
+            size: Vec2::new(width, height),
+            offset: Vec2::new(-width / 2., -WORKSTATION_SPRITE_SIZE / 2.),
+        }))
+    }
+
+    pub fn frame(&self, working: bool) -> usize {
+        self.sprite * WORKSTATION_SHEET_COLUMNS + working as usize
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct RecipeDefinition {
+    pub name: String,
+    pub inputs: Vec<(String, u32)>,
+    pub outputs: Vec<(String, u32)>,
+    pub seconds: f32,
+    // recipes without one are crafted by hand
+    #[serde(default)]
+    pub workstation: Option<String>,
+    // locked recipes are unknown until unlocked
+    #[serde(default)]
+    pub locked: bool,
+    // items that unlock the recipe once the player first gets them
+    #[serde(default)]
+    pub unlocked_by: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct CraftingData {
+    pub workstations: HashMap<String, WorkstationDefinition>,
+    pub recipes: HashMap<String, RecipeDefinition>,
+}
+
+#[derive(Default, Resource)]
+pub struct RecipeBook {
+    pub data: CraftingData,
+    pub atlas: Option<Handle<TextureAtlas>>,
+}
+
+impl RecipeBook {
+    // recipes and workstations that don't match the item database are left out
+    pub fn load(items: &ItemDatabase) -> Self {
+        let mut data: CraftingData = read_ron(CRAFTING_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load crafting data from {}", CRAFTING_DATA_PATH);
+            CraftingData {..default()}
+        });
+        data.workstations.retain(|kind, workstation| {
+            let errors = validate_workstation(workstation);
+            for error in errors.iter() {
+                warn!("Workstation {} {}", kind, error);
+            }
+            errors.is_empty()
+        });
+        let workstations = &data.workstations;
+        data.recipes.retain(|name, recipe| {
+            let errors = validate_recipe(recipe, workstations, items);
+            for error in errors.iter() {
+                warn!("Recipe {} {}", name, error);
+            }
+            errors.is_empty()
+        });
+        RecipeBook { data, ..default() }
+    }
+
+    pub fn get(&self, recipe: &str) -> Option<&RecipeDefinition> {
+        self.data.recipes.get(recipe)
+    }
+
+    pub fn workstation(&self, kind: &str) -> Option<&WorkstationDefinition> {
+        self.data.workstations.get(kind)
+    }
+
+    // the known recipes made at a workstation, or by hand without one, in menu order
+    pub fn recipes_at(&self, workstation: Option<&str>, known: &KnownRecipes) -> Vec<(&String, &RecipeDefinition)> {
+        let mut recipes: Vec<_> = self.data.recipes.iter()
+            .filter(|(name, recipe)| recipe.workstation.as_deref() == workstation && known.knows(name))
+            .collect();
+        recipes.sort_by(|(a_name, a), (b_name, b)| a.name.cmp(&b.name).then(a_name.cmp(b_name)));
+        recipes
+    }
+
+    pub fn atlas(&mut self, asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>) -> Handle<TextureAtlas> {
+        self.atlas.get_or_insert_with(|| {
+            texture_atlases.add(TextureAtlas::from_grid(
+                asset_server.load(WORKSTATION_SPRITES_PATH), Vec2::splat(WORKSTATION_SPRITE_SIZE),
+                WORKSTATION_SHEET_COLUMNS, WORKSTATION_SHEET_ROWS, None, None
+            ))
+        }).clone()
+    }
+}
+
+#[derive(Clone, Default, Resource, Serialize, Deserialize)]
+pub struct KnownRecipes {
+    pub recipes: BTreeSet<String>,
+}
+
+impl KnownRecipes {
+    // recipes that aren't locked are known from the start
+    pub fn new(book: &RecipeBook) -> Self {
+        KnownRecipes {
+            recipes: book.data.recipes.iter()
+                .filter(|(_, recipe)| !recipe.locked)
+                .map(|(name, _)| name.clone())
+                .collect(),
+        }
+    }
+
+    pub fn knows(&self, recipe: &str) -> bool {
+        self.recipes.contains(recipe)
+    }
+
+    // returns whether the recipe was new
+    pub fn unlock(&mut self, recipe: &str) -> bool {
+        self.recipes.insert(recipe.to_string())
+    }
+
+    // recipes unlocked since the save was made stay known
+    pub fn restore(&mut self, saved: KnownRecipes, book: &RecipeBook) {
+        *self = KnownRecipes::new(book);
+        self.recipes.extend(saved.recipes);
+    }
+}
+
+pub struct RecipeUnlocked {
+    pub recipe: String,
+}
+
+pub struct Crafted {
+    pub crafter: Entity,
+    pub recipe: String,
+}
+
+pub struct CraftJob {
+    pub recipe: String,
+    pub timer: Timer,
+}
+
+impl CraftJob {
+    pub fn new(recipe: &str, definition: &RecipeDefinition) -> Self {
+        CraftJob { recipe: recipe.to_string(), timer: Timer::from_seconds(definition.seconds, TimerMode::Once) }
+    }
+}
+
+// jobs are crafted one after the other, their inputs are taken when queued
+#[derive(Default, Component)]
+pub struct CraftingQueue {
+    pub jobs: VecDeque<CraftJob>,
+}
+
+#[derive(Component)]
+pub struct Workstation {
+    pub kind: String,
+}
+
+// placed in LDtk, replaced by the workstation's components once spawned
+#[derive(Default, Component)]
+pub struct WorkstationSpawn {
+    pub kind: Option<String>,
+}
+
+impl From<EntityInstance> for WorkstationSpawn {
+    fn from(entity_instance: EntityInstance) -> WorkstationSpawn {
+        WorkstationSpawn { kind: entity_string(&entity_instance, STATION_FIELD) }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct WorkstationSpawnBundle {
+    #[from_entity_instance]
+    pub spawn: WorkstationSpawn,
+}
+
+#[derive(Bundle)]
+pub struct WorkstationBundle {
+    pub workstation: Workstation,
+    pub queue: CraftingQueue,
+    pub interactable: Interactable,
+    pub sprite_zone: SpriteZone,
+}
+
+impl WorkstationBundle {
+    pub fn new(kind: &str, definition: &WorkstationDefinition) -> Self {
+        let size = Vec2::splat(WORKSTATION_SPRITE_SIZE);
+        WorkstationBundle {
+            workstation: Workstation { kind: kind.to_string() },
+            queue: CraftingQueue {..default()},
+            interactable: Interactable { prompt: format!("Use {}", definition.name), ..default() },
+            sprite_zone: SpriteZone(TransformZone { size, offset: -size / 2. }),
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct CraftingMenu {
+    pub open: bool,
+    // the workstation in use, crafting is by hand without one
+    pub station: Option<Entity>,
+}
+
+#[derive(Default, Component)]
+pub struct CraftingMenuText;
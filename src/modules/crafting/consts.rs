@@ -0,0 +1,21 @@
+use bevy::prelude::KeyCode;
+
+pub const CRAFTING_DATA_PATH: &str = "assets/data/crafting.ron";
+
+pub const WORKSTATION_SPRITES_PATH: &str = "sprites/workstations/texture.png";
+pub const WORKSTATION_SPRITE_SIZE: f32 = 16.;
+// every workstation has a row with its idle and working frame
+pub const WORKSTATION_SHEET_COLUMNS: usize = 2;
+pub const WORKSTATION_SHEET_ROWS: usize = 4;
+pub const WORKSTATION_Z: f32 = 3.;
+
+pub const CRAFT_KEY: KeyCode = KeyCode::C;
+pub const CRAFTING_MENU: &str = "crafting";
+pub const CLOSE_MENU_KEY: KeyCode = KeyCode::Escape;
+pub const RECIPE_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+    KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9
+];
+pub const MAX_QUEUED_JOBS: usize = 5;
+pub const CRAFT_SOUND: &str = "ui/craft";
+pub const MENU_FONT_SIZE: f32 = 12.;
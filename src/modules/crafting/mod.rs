@@ -0,0 +1,4 @@
+pub mod components;
+pub mod consts;
+pub mod systems;
+pub mod validation;
\ No newline at end of file
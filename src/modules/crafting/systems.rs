@@ -0,0 +1,282 @@
+use bevy::asset::{AssetServer, Assets};
+use bevy::hierarchy::Parent;
+use bevy::input::Input;
+use bevy::log::{info, warn};
+use bevy::math::Vec2;
+use bevy::prelude::{Added, Commands, ComputedVisibility, Entity, EventReader, EventWriter, GlobalTransform, KeyCode, Query, Res, ResMut, Text, TextureAtlasSprite, Time, Visibility, With, Without, World};
+use bevy::sprite::TextureAtlas;
+use bevy_ecs_ldtk::EntityInstance;
+use crate::modules::crafting::components::{CraftJob, Crafted, CraftingMenu, CraftingMenuText, CraftingQueue, KnownRecipes, RecipeBook, RecipeUnlocked, Workstation, WorkstationBundle, WorkstationSpawn};
+use crate::modules::crafting::consts::{CLOSE_MENU_KEY, CRAFTING_MENU, CRAFT_KEY, CRAFT_SOUND, MAX_QUEUED_JOBS, RECIPE_KEYS};
+use crate::modules::input::components::OpenMenus;
+use crate::modules::interaction::components::{Interacted, InteractionFocus};
+use crate::modules::inventory::components::{Inventory, InventoryChanged, ItemDatabase};
+use crate::modules::inventory::systems::{level_of, spawn_ground_items};
+use crate::modules::level::components::LevelTransition;
+use crate::modules::living::components::Player;
+use crate::modules::rng::components::RngResource;
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
+
+// the book checks recipes against the items, and what it drops is only printed once the log plugin is built
+pub fn load_recipes(world: &mut World) {
+    let book = RecipeBook::load(world.resource::<ItemDatabase>());
+    world.insert_resource(KnownRecipes::new(&book));
+    world.insert_resource(book);
+}
+
+pub fn spawn_placed_workstations(
+    mut commands: Commands, asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut book: ResMut<RecipeBook>, spawns: Query<(Entity, &WorkstationSpawn, &EntityInstance), Added<WorkstationSpawn>>
+) {
+    for (entity, spawn, entity_instance) in spawns.iter() {
+        let definition = match spawn.kind.as_ref().and_then(|kind| book.workstation(kind)) {
+            Some(definition) => definition.clone(),
+            None => {
+                warn!("Workstation {} has no known station {:?}", entity_instance.iid, spawn.kind);
+                continue;
+            }
+        };
+        let texture_atlas = book.atlas(&asset_server, &mut texture_atlases);
+        let mut workstation = commands.entity(entity);
+        workstation.insert((
+            WorkstationBundle::new(spawn.kind.as_deref().unwrap_or_default(), &definition), texture_atlas,
+            TextureAtlasSprite::new(definition.frame(false)), Visibility::VISIBLE, ComputedVisibility::default()
+        ));
+        if let Some(collider) = definition.collider() {
+            workstation.insert(collider);
+        }
+    }
+}
+
+// a workstation's menu stays open while it is the interaction focus
+#[allow(clippy::too_many_arguments)]
+pub fn toggle_crafting_menu(
+    keyboard_input: Res<Input<KeyCode>>, mut interactions: EventReader<Interacted>, mut menu: ResMut<CraftingMenu>,
+    mut open_menus: ResMut<OpenMenus>, focus: Res<InteractionFocus>, transition: Res<LevelTransition>,
+    players: Query<Entity, With<Player>>, workstations: Query<(), With<Workstation>>
+) {
+    let mut open = menu.open;
+    let mut station = menu.station;
+    for interaction in interactions.iter() {
+        if players.get(interaction.actor).is_err() || workstations.get(interaction.target).is_err() {
+            continue;
+        }
+        open = !(open && station == Some(interaction.target));
+        station = Some(interaction.target);
+    }
+    if keyboard_input.just_pressed(CRAFT_KEY) {
+        open = !(open && station.is_none());
+        station = None;
+    }
+    if keyboard_input.just_pressed(CLOSE_MENU_KEY) || transition.is_active() ||
+        station.is_some_and(|station| focus.target != Some(station)) {
+        open = false;
+    }
+    if menu.open != open || menu.station != station {
+        menu.open = open;
+        menu.station = station;
+        open_menus.set(CRAFTING_MENU, open);
+    }
+}
+
+pub fn queue_recipes(
+    keyboard_input: Res<Input<KeyCode>>, menu: Res<CraftingMenu>, book: Res<RecipeBook>, known: Res<KnownRecipes>,
+    mut players: Query<(Entity, &mut Inventory, &mut CraftingQueue), With<Player>>,
+    mut workstations: Query<(&Workstation, &mut CraftingQueue), Without<Player>>,
+    mut inventory_events: EventWriter<InventoryChanged>
+) {
+    if !menu.open {
+        return;
+    }
+    let index = match RECIPE_KEYS.iter().position(|key| keyboard_input.just_pressed(*key)) {
+        Some(index) => index,
+        None => return
+    };
+    let (player, mut inventory, hand_queue) = match players.get_single_mut() {
+        Ok(player) => player,
+        _ => return
+    };
+    let (kind, queue) = match menu.station {
+        Some(station) => match workstations.get_mut(station) {
+            Ok((workstation, queue)) => (Some(workstation.kind.as_str()), queue.into_inner()),
+            Err(_) => return
+        },
+        None => (None, hand_queue.into_inner())
+    };
+    let (name, recipe) = match book.recipes_at(kind, &known).get(index) {
+        Some(recipe) => *recipe,
+        None => return
+    };
+    if queue.jobs.len() >= MAX_QUEUED_JOBS || !recipe.inputs.iter().all(|(item, count)| inventory.has(item, *count)) {
+        return;
+    }
+    for (item, count) in recipe.inputs.iter() {
+        inventory.remove(item, *count);
+        inventory_events.send(InventoryChanged { entity: player, item: item.clone(), delta: -(*count as i64) });
+    }
+    queue.jobs.push_back(CraftJob::new(name, recipe));
+}
+
+// crafters with an inventory keep what they make, workstations drop it at their feet
+#[allow(clippy::too_many_arguments)]
+pub fn update_crafting_queues(
+    mut commands: Commands, time: Res<Time>, book: Res<RecipeBook>, mut items: ResMut<ItemDatabase>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>, mut rng: ResMut<RngResource>,
+    mut crafters: Query<(Entity, &mut CraftingQueue, Option<&mut Inventory>)>,
+    parents: Query<&Parent>, transforms: Query<&GlobalTransform>,
+    mut inventory_events: EventWriter<InventoryChanged>, mut crafted_events: EventWriter<Crafted>,
+    mut sound_events: EventWriter<SoundEvent>
+) {
+    for (crafter, mut queue, mut inventory) in crafters.iter_mut() {
+        let job = match queue.jobs.front_mut() {
+            Some(job) => job,
+            None => continue
+        };
+        if !job.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let recipe = match queue.jobs.pop_front() {
+            Some(job) => job.recipe,
+            None => continue
+        };
+        let outputs = match book.get(&recipe) {
+            Some(definition) => definition.outputs.clone(),
+            None => continue
+        };
+        let mut drops = Vec::new();
+        for (item, count) in outputs {
+            let left = match inventory.as_mut() {
+                Some(inventory) => inventory.add(&item, count, &items),
+                None => count
+            };
+            if left < count {
+                inventory_events.send(InventoryChanged { entity: crafter, item: item.clone(), delta: (count - left) as i64 });
+            }
+            if left > 0 {
+                drops.push((item, left));
+            }
+        }
+        if !drops.is_empty() {
+            let position = transforms.get(crafter).map_or(Vec2::ZERO, |transform| transform.translation().truncate());
+            spawn_ground_items(
+                &mut commands, &mut items, &asset_server, &mut texture_atlases, &mut rng, &drops,
+                position, level_of(crafter, &parents, &transforms)
+            );
+        }
+        crafted_events.send(Crafted { crafter, recipe });
+        sound_events.send(SoundEvent {
+            path: CRAFT_SOUND.to_string(),
+            sound_type: SoundType::RANDOM,
+            file: String::new(),
+            channel: SoundChannel::UI,
+            emitter: None,
+        });
+    }
+}
+
+pub fn unlock_recipes(
+    mut inventory_events: EventReader<InventoryChanged>, book: Res<RecipeBook>, mut known: ResMut<KnownRecipes>,
+    players: Query<(), With<Player>>, mut unlocked_events: EventWriter<RecipeUnlocked>
+) {
+    for inventory_event in inventory_events.iter() {
+        if inventory_event.delta <= 0 || players.get(inventory_event.entity).is_err() {
+            continue;
+        }
+        for (name, recipe) in book.data.recipes.iter() {
+            if !recipe.unlocked_by.contains(&inventory_event.item) || known.knows(name) {
+                continue;
+            }
+            known.unlock(name);
+            info!("Learnt to craft {}", recipe.name);
+            unlocked_events.send(RecipeUnlocked { recipe: name.clone() });
+        }
+    }
+}
+
+pub fn update_workstation_sprites(
+    book: Res<RecipeBook>, mut workstations: Query<(&Workstation, &CraftingQueue, &mut TextureAtlasSprite)>
+) {
+    for (workstation, queue, mut sprite) in workstations.iter_mut() {
+        let index = match book.workstation(&workstation.kind) {
+            Some(definition) => definition.frame(!queue.jobs.is_empty()),
+            None => continue
+        };
+        if sprite.index != index {
+            sprite.index = index;
+        }
+    }
+}
+
+pub fn update_crafting_menu(
+    menu: Res<CraftingMenu>, book: Res<RecipeBook>, known: Res<KnownRecipes>, items: Res<ItemDatabase>,
+    players: Query<(&Inventory, &CraftingQueue), With<Player>>,
+    workstations: Query<(&Workstation, &CraftingQueue), Without<Player>>,
+    mut menu_texts: Query<(&mut Text, &mut Visibility), With<CraftingMenuText>>
+) {
+    let (inventory, hand_queue) = match players.get_single() {
+        Ok(player) => player,
+        _ => return
+    };
+    let station = menu.station.and_then(|station| workstations.get(station).ok());
+    let (title, kind, queue) = match station {
+        Some((workstation, queue)) => (
+            book.workstation(&workstation.kind).map_or(workstation.kind.clone(), |definition| definition.name.clone()),
+            Some(workstation.kind.as_str()), queue
+        ),
+        None => ("Crafting".to_string(), None, hand_queue)
+    };
+    let item_name = |item: &str| items.get(item).map_or(item.to_string(), |definition| definition.name.clone());
+
+    let mut lines = vec![title];
+    let recipes = book.recipes_at(kind, &known);
+    if recipes.is_empty() {
+        lines.push("Nothing to craft here yet".to_string());
+    }
+    for (index, (_, recipe)) in recipes.iter().take(RECIPE_KEYS.len()).enumerate() {
+        let inputs: Vec<_> = recipe.inputs.iter()
+            .map(|(item, count)| format!("{} {}", count, item_name(item)))
+            .collect();
+        let missing = if recipe.inputs.iter().all(|(item, count)| inventory.has(item, *count)) { "" } else { " (missing)" };
+        lines.push(format!("{}. {} - {} ({}s){}", index + 1, recipe.name, inputs.join(", "), recipe.seconds, missing));
+    }
+    if !queue.jobs.is_empty() {
+        let jobs: Vec<_> = queue.jobs.iter().enumerate()
+            .map(|(index, job)| {
+                let name = book.get(&job.recipe).map_or(job.recipe.clone(), |recipe| recipe.name.clone());
+                match index {
+                    0 => format!("{} {}%", name, (job.timer.percent() * 100.) as u32),
+                    _ => name
+                }
+            })
+            .collect();
+        lines.push(format!("Queue: {}", jobs.join(", ")));
+    }
+    let value = lines.join("\n");
+
+    for (mut text, mut visibility) in menu_texts.iter_mut() {
+        if visibility.is_visible != menu.open {
+            visibility.is_visible = menu.open;
+        }
+        if menu.open && text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, StartupStage};
+    use crate::modules::testing::logs::capture_warnings;
+    use super::*;
+
+    #[test]
+    fn loading_the_book_warns_about_dropped_recipes() {
+        let mut app = App::new();
+        app.insert_resource(ItemDatabase::default())
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_recipes);
+        let warnings = capture_warnings(|| app.update());
+        assert!(warnings.contains("Recipe "), "{}", warnings);
+        assert!(app.world.contains_resource::<RecipeBook>());
+        assert!(app.world.contains_resource::<KnownRecipes>());
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use crate::modules::crafting::components::{RecipeDefinition, WorkstationDefinition};
+use crate::modules::crafting::consts::WORKSTATION_SHEET_ROWS;
+use crate::modules::inventory::components::ItemDatabase;
+
+pub fn validate_workstation(workstation: &WorkstationDefinition) -> Vec<String> {
+    let mut errors = Vec::new();
+    if workstation.sprite >= WORKSTATION_SHEET_ROWS {
+        errors.push(format!("uses sprite row {} of a sheet with {}", workstation.sprite, WORKSTATION_SHEET_ROWS));
+    }
+    errors
+}
+
+pub fn validate_recipe(
+    recipe: &RecipeDefinition, workstations: &HashMap<String, WorkstationDefinition>, items: &ItemDatabase
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (item, count) in recipe.inputs.iter() {
+        check_item(item, "needs", items, &mut errors);
+        if *count == 0 {
+            errors.push(format!("needs no {}", item));
+        }
+    }
+    for (item, count) in recipe.outputs.iter() {
+        check_item(item, "makes", items, &mut errors);
+        if *count == 0 {
+            errors.push(format!("makes no {}", item));
+        }
+    }
+    for item in recipe.unlocked_by.iter() {
+        check_item(item, "is unlocked by", items, &mut errors);
+    }
+    if recipe.outputs.is_empty() {
+        errors.push("makes nothing".to_string());
+    }
+    if recipe.seconds.is_nan() || recipe.seconds < 0. {
+        errors.push(format!("takes {} seconds", recipe.seconds));
+    }
+    if let Some(workstation) = &recipe.workstation {
+        if !workstations.contains_key(workstation) {
+            errors.push(format!("is made at unknown workstation {}", workstation));
+        }
+    }
+    if !recipe.locked && !recipe.unlocked_by.is_empty() {
+        errors.push("is unlocked by items but not locked".to_string());
+    }
+    errors
+}
+
+fn check_item(item: &str, role: &str, items: &ItemDatabase, errors: &mut Vec<String>) {
+    if items.get(item).is_none() {
+        errors.push(format!("{} unknown item {}", role, item));
+    }
+}
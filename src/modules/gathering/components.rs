@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, TimerMode};
+use bevy::time::Timer;
+use bevy::utils::default;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use crate::modules::gathering::consts::{GATHERING_DATA_PATH, GATHER_FRAME_SECONDS, GATHER_SECONDS, HAND_POWER, HIT_FLASH_SECONDS};
+use crate::modules::storage::files::read_ron;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+pub enum ToolKind { #[default] HAND, AXE, PICKAXE }
+
+// (kind, power) of the tools an entity gathers with, bare hands need none
+#[derive(Default, Component)]
+pub struct Tools {
+    pub carried: Vec<(ToolKind, f32)>,
+}
+
+impl Tools {
+    pub fn power(&self, kind: ToolKind) -> Option<f32> {
+        self.carried.iter()
+            .filter(|(carried_kind, _)| *carried_kind == kind)
+            .map(|(_, power)| *power)
+            .reduce(f32::max)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct DropEntry {
+    pub item: String,
+    pub weight: f32,
+    pub count: (u32, u32),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct NodeDefinition {
+    pub prompt: String,
+    pub durability: f32,
+    // HAND nodes can be gathered with anything
+    pub tool: ToolKind,
+    // otherwise bare hands work too, only slower than the tool
+    #[serde(default)]
+    pub needs_tool: bool,
+    pub sound: String,
+    pub rolls: (u32, u32),
+    pub drops: Vec<DropEntry>,
+    // depleted nodes without one stay depleted
+    pub regrow_seconds: Option<f32>,
+    // index into the node's own sprite sheet shown while depleted
+    #[serde(default)]
+    pub depleted_frame: Option<usize>,
+}
+
+impl NodeDefinition {
+    pub fn accepts(&self, tools: &Tools) -> bool {
+        self.tool == ToolKind::HAND || !self.needs_tool || tools.power(self.tool).is_some()
+    }
+
+    // a tool only helps on the nodes it is made for
+    pub fn power(&self, tools: &Tools) -> f32 {
+        tools.power(self.tool).unwrap_or(HAND_POWER)
+    }
+
+    pub fn roll_drops(&self, rng: &mut impl Rng) -> Vec<(String, u32)> {
+        roll_drops(self.rolls, &self.drops, rng)
+    }
+}
+
+// (item, count) pairs, items rolled more than once are merged
+pub fn roll_drops(rolls: (u32, u32), drops: &[DropEntry], rng: &mut impl Rng) -> Vec<(String, u32)> {
+    let mut rolled: Vec<(String, u32)> = Vec::new();
+    for _ in 0..rng.gen_range(rolls.0..=rolls.1.max(rolls.0)) {
+        let drop = match drops.choose_weighted(rng, |drop| drop.weight) {
+            Ok(drop) => drop,
+            Err(_) => break
+        };
+        let count = rng.gen_range(drop.count.0..=drop.count.1.max(drop.count.0));
+        match rolled.iter_mut().find(|(item, _)| *item == drop.item) {
+            Some((_, total)) => *total += count,
+            None => rolled.push((drop.item.clone(), count))
+        }
+    }
+    rolled
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct GatheringData {
+    pub nodes: HashMap<String, NodeDefinition>,
+}
+
+#[derive(Default, Resource)]
+pub struct GatheringNodes {
+    pub data: GatheringData,
+}
+
+impl GatheringNodes {
+    pub fn load() -> Self {
+        let data = read_ron(GATHERING_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load gathering nodes from {}", GATHERING_DATA_PATH);
+            GatheringData {..default()}
+        });
+        GatheringNodes { data }
+    }
+
+    pub fn get(&self, identifier: &str) -> Option<&NodeDefinition> {
+        self.data.nodes.get(identifier)
+    }
+}
+
+#[derive(Component)]
+pub struct Harvestable {
+    pub identifier: String,
+    pub durability: f32,
+    pub depleted: bool,
+    pub hit_timer: Timer,
+    // the sprite's frame while it can be gathered
+    pub frame: usize,
+}
+
+impl Harvestable {
+    pub fn new(identifier: &str, durability: f32, frame: usize) -> Self {
+        let mut hit_timer = Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once);
+        hit_timer.tick(hit_timer.duration());
+        Harvestable { identifier: identifier.to_string(), durability, depleted: durability <= 0., hit_timer, frame }
+    }
+}
+
+// the swing of an entity gathering, it drives the sprite until it ends
+#[derive(Component)]
+pub struct Gathering {
+    pub timer: Timer,
+    pub frame_timer: Timer,
+    pub frame: usize,
+}
+
+impl Default for Gathering {
+    fn default() -> Self {
+        Gathering {
+            timer: Timer::from_seconds(GATHER_SECONDS, TimerMode::Once),
+            frame_timer: Timer::from_seconds(GATHER_FRAME_SECONDS, TimerMode::Repeating),
+            frame: 0,
+        }
+    }
+}
+
+pub struct GatherEvent {
+    pub actor: Entity,
+    pub node: Entity,
+    pub identifier: String,
+    pub drops: Vec<(String, u32)>,
+    pub position: Vec2,
+}
@@ -0,0 +1,14 @@
+pub const GATHERING_DATA_PATH: &str = "assets/data/gathering.ron";
+
+// world state keys of a node
+pub const DURABILITY_KEY: &str = "durability";
+pub const REGROW_AT_KEY: &str = "regrow_at";
+
+pub const HAND_POWER: f32 = 1.;
+
+// a gather swing, the next one can't start before it ends
+pub const GATHER_SECONDS: f32 = 0.4;
+pub const GATHER_FRAME_SECONDS: f32 = 0.1;
+
+pub const HIT_FLASH_SECONDS: f32 = 0.12;
+pub const HIT_TINT: (f32, f32, f32) = (1., 0.85, 0.6);
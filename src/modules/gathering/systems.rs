@@ -0,0 +1,181 @@
+use bevy::prelude::{Added, Color, Commands, Entity, EventReader, EventWriter, GlobalTransform, Query, Res, ResMut, TextureAtlasSprite, Time, With, Without};
+use bevy_ecs_ldtk::EntityInstance;
+use crate::modules::gathering::components::{GatherEvent, Gathering, GatheringNodes, Harvestable, Tools};
+use crate::modules::gathering::consts::{DURABILITY_KEY, HIT_TINT, REGROW_AT_KEY};
+use crate::modules::interaction::components::{Interactable, Interacted};
+use crate::modules::living::components::Player;
+use crate::modules::physics::sprite_change::components::{AnimationOverride, MultipleSided, Side};
+use crate::modules::physics::sprite_change::consts::{MOB_BOTTOM_IDLE_START, MOB_BOTTOM_WALK_START, MOB_SIDE_IDLE_START, MOB_SIDE_WALK_START, MOB_TOP_IDLE_START, MOB_TOP_WALK_START};
+use crate::modules::prop::components::Prop;
+use crate::modules::rng::components::{RngResource, RngStream};
+use crate::modules::save::components::Playtime;
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
+use crate::modules::world_state::components::WorldState;
+
+pub fn attach_harvestables(
+    mut commands: Commands, nodes: Res<GatheringNodes>, world_state: Res<WorldState>,
+    added: Query<(Entity, &EntityInstance, Option<&TextureAtlasSprite>), Added<Prop>>
+) {
+    for (entity, entity_instance, sprite) in added.iter() {
+        let definition = match nodes.get(&entity_instance.identifier) {
+            Some(definition) => definition,
+            None => continue
+        };
+        let durability = world_state.value(&entity_instance.iid, DURABILITY_KEY).unwrap_or(definition.durability);
+        let frame = sprite.map_or(0, |sprite| sprite.index);
+        commands.entity(entity).insert(Harvestable::new(&entity_instance.identifier, durability, frame));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn gather(
+    mut commands: Commands, mut interactions: EventReader<Interacted>, nodes: Res<GatheringNodes>,
+    mut world_state: ResMut<WorldState>, playtime: Res<Playtime>, mut rng: ResMut<RngResource>,
+    actors: Query<(Option<&Tools>, Option<&Gathering>)>,
+    mut harvestables: Query<(&mut Harvestable, &EntityInstance, &GlobalTransform)>,
+    mut sound_events: EventWriter<SoundEvent>, mut gather_events: EventWriter<GatherEvent>
+) {
+    for interaction in interactions.iter() {
+        let (mut harvestable, entity_instance, transform) = match harvestables.get_mut(interaction.target) {
+            Ok(harvestable) => harvestable,
+            Err(_) => continue
+        };
+        let (tools, gathering) = match actors.get(interaction.actor) {
+            Ok(actor) => actor,
+            Err(_) => continue
+        };
+        let definition = match nodes.get(&harvestable.identifier) {
+            Some(definition) => definition,
+            None => continue
+        };
+        let no_tools = Tools::default();
+        let tools = tools.unwrap_or(&no_tools);
+        if harvestable.depleted || gathering.is_some() || !definition.accepts(tools) {
+            continue;
+        }
+
+        harvestable.durability = (harvestable.durability - definition.power(tools)).max(0.);
+        harvestable.hit_timer.reset();
+        world_state.set_value(&entity_instance.iid, DURABILITY_KEY, harvestable.durability);
+        commands.entity(interaction.actor).insert((Gathering::default(), AnimationOverride));
+        sound_events.send(SoundEvent {
+            path: definition.sound.clone(),
+            sound_type: SoundType::RANDOM,
+            file: String::new(),
+            channel: SoundChannel::SFX,
+            emitter: Some(interaction.target),
+        });
+
+        if harvestable.durability <= 0. {
+            harvestable.depleted = true;
+            if let Some(regrow_seconds) = definition.regrow_seconds {
+                world_state.set_value(&entity_instance.iid, REGROW_AT_KEY, playtime.seconds as f32 + regrow_seconds);
+            }
+            gather_events.send(GatherEvent {
+                actor: interaction.actor,
+                node: interaction.target,
+                identifier: harvestable.identifier.clone(),
+                drops: definition.roll_drops(rng.stream(RngStream::LOOT)),
+                position: transform.translation().truncate(),
+            });
+        }
+    }
+}
+
+// regrowth is kept as a playtime so it carries on across level loads and saves
+pub fn regrow_harvestables(
+    nodes: Res<GatheringNodes>, mut world_state: ResMut<WorldState>, playtime: Res<Playtime>,
+    mut harvestables: Query<(&mut Harvestable, &EntityInstance)>
+) {
+    for (mut harvestable, entity_instance) in harvestables.iter_mut() {
+        if !harvestable.depleted {
+            continue;
+        }
+        let regrow_at = match world_state.value(&entity_instance.iid, REGROW_AT_KEY) {
+            Some(regrow_at) => regrow_at,
+            None => continue
+        };
+        if (playtime.seconds as f32) < regrow_at {
+            continue;
+        }
+        if let Some(definition) = nodes.get(&harvestable.identifier) {
+            harvestable.durability = definition.durability;
+            harvestable.depleted = false;
+            world_state.set_value(&entity_instance.iid, DURABILITY_KEY, definition.durability);
+        }
+    }
+}
+
+pub fn update_harvestable_prompts(
+    nodes: Res<GatheringNodes>, players: Query<Option<&Tools>, With<Player>>,
+    mut harvestables: Query<(&Harvestable, &mut Interactable)>
+) {
+    let no_tools = Tools::default();
+    let tools = players.get_single().ok().flatten().unwrap_or(&no_tools);
+    for (harvestable, mut interactable) in harvestables.iter_mut() {
+        let definition = match nodes.get(&harvestable.identifier) {
+            Some(definition) => definition,
+            None => continue
+        };
+        let prompt = if definition.accepts(tools) {
+            definition.prompt.clone()
+        } else {
+            format!("{} (needs {:?})", definition.prompt, definition.tool)
+        };
+        let enabled = !harvestable.depleted;
+        if interactable.prompt != prompt || interactable.enabled != enabled {
+            interactable.prompt = prompt;
+            interactable.enabled = enabled;
+        }
+    }
+}
+
+pub fn update_harvestable_sprites(
+    time: Res<Time>, nodes: Res<GatheringNodes>, mut harvestables: Query<(&mut Harvestable, &mut TextureAtlasSprite)>
+) {
+    for (mut harvestable, mut sprite) in harvestables.iter_mut() {
+        harvestable.hit_timer.tick(time.delta());
+        let color = if harvestable.hit_timer.finished() {
+            Color::WHITE
+        } else {
+            Color::rgb(HIT_TINT.0, HIT_TINT.1, HIT_TINT.2)
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+        let depleted_frame = nodes.get(&harvestable.identifier).and_then(|definition| definition.depleted_frame);
+        let frame = match depleted_frame {
+            Some(depleted_frame) if harvestable.depleted => depleted_frame,
+            _ => harvestable.frame
+        };
+        if sprite.index != frame {
+            sprite.index = frame;
+        }
+    }
+}
+
+// swings between the stepping and standing frame of the side the gatherer faces
+#[allow(clippy::type_complexity)]
+pub fn update_gathering(
+    mut commands: Commands, time: Res<Time>,
+    mut gatherers: Query<(Entity, &mut Gathering, Option<&MultipleSided>, Option<&mut TextureAtlasSprite>), Without<Harvestable>>
+) {
+    for (entity, mut gathering, multiple_sided, sprite) in gatherers.iter_mut() {
+        if gathering.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<(Gathering, AnimationOverride)>();
+            continue;
+        }
+        if gathering.frame_timer.tick(time.delta()).just_finished() {
+            gathering.frame += 1;
+        }
+        if let (Some(multiple_sided), Some(mut sprite)) = (multiple_sided, sprite) {
+            let frames = match multiple_sided.side {
+                Side::BOTTOM => [MOB_BOTTOM_WALK_START, MOB_BOTTOM_IDLE_START],
+                Side::TOP => [MOB_TOP_WALK_START, MOB_TOP_IDLE_START],
+                Side::LEFT | Side::RIGHT => [MOB_SIDE_WALK_START, MOB_SIDE_IDLE_START],
+            };
+            sprite.index = frames[gathering.frame % frames.len()];
+            sprite.flip_x = multiple_sided.side == Side::RIGHT;
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use bevy::ecs::prelude::Resource;
+use bevy::prelude::Entity;
+use bevy::utils::HashMap;
+
+#[derive(Default, Resource)]
+pub struct HotReloadState {
+    pub entities: HashMap<String, String>,
+    pub detached_player: Option<Entity>,
+}
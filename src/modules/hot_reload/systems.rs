@@ -0,0 +1,89 @@
+use bevy::asset::{AssetEvent, Assets, Handle};
+use bevy::log::info;
+use bevy::prelude::{BuildChildren, Commands, Entity, EventReader, Query, Res, ResMut, With};
+use bevy::utils::HashMap;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::prelude::{LdtkAsset, LevelEvent};
+use crate::modules::hot_reload::components::HotReloadState;
+use crate::modules::level::systems::project_entities;
+use crate::modules::living::components::Player;
+use crate::modules::physics::components::{Collider, Physical, SelfPhysical};
+use crate::modules::physics::sprite_change::components::SpriteZone;
+
+// runs before bevy_ecs_ldtk respawns the world so the player survives the reload
+pub fn detach_player_on_reload(
+    mut commands: Commands, mut state: ResMut<HotReloadState>, mut asset_events: EventReader<AssetEvent<LdtkAsset>>,
+    ldtk_assets: Res<Assets<LdtkAsset>>, players: Query<Entity, With<Player>>
+) {
+    for asset_event in asset_events.iter() {
+        match asset_event {
+            AssetEvent::Created { handle } => {
+                if let Some(ldtk_asset) = ldtk_assets.get(handle) {
+                    state.entities = entity_identifiers(ldtk_asset);
+                }
+            }
+            AssetEvent::Modified { handle } => {
+                if let Some(ldtk_asset) = ldtk_assets.get(handle) {
+                    let entities = entity_identifiers(ldtk_asset);
+                    report_changes(&state.entities, &entities);
+                    state.entities = entities;
+                }
+                if let Ok(player) = players.get_single() {
+                    commands.entity(player).remove_parent();
+                    state.detached_player = Some(player);
+                }
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+
+    fn entity_identifiers(ldtk_asset: &LdtkAsset) -> HashMap<String, String> {
+        project_entities(ldtk_asset)
+            .map(|entity_instance| (entity_instance.iid.clone(), entity_instance.identifier.clone()))
+            .collect()
+    }
+
+    fn report_changes(previous: &HashMap<String, String>, current: &HashMap<String, String>) {
+        for (iid, identifier) in current.iter().filter(|(iid, _)| !previous.contains_key(*iid)) {
+            info!("Hot reload added {} ({})", identifier, iid);
+        }
+        for (iid, identifier) in previous.iter().filter(|(iid, _)| !current.contains_key(*iid)) {
+            info!("Hot reload removed {} ({})", identifier, iid);
+        }
+    }
+}
+
+pub fn reattach_player_after_reload(
+    mut commands: Commands, mut state: ResMut<HotReloadState>, mut level_events: EventReader<LevelEvent>,
+    ldtk_worlds: Query<(Entity, &Handle<LdtkAsset>)>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut players: Query<(&mut EntityInstance, &mut Physical, &mut SelfPhysical, &mut Collider, &mut SpriteZone), With<Player>>
+) {
+    let level_transformed = level_events.iter()
+        .filter(|level_event| matches!(level_event, LevelEvent::Transformed(_)))
+        .count() > 0;
+    let player = match state.detached_player {
+        Some(player) if level_transformed => player,
+        _ => return
+    };
+    state.detached_player = None;
+    let (world, ldtk_handle) = match ldtk_worlds.get_single() {
+        Ok(ldtk_world) => ldtk_world,
+        _ => return
+    };
+    commands.entity(world).add_child(player);
+
+    let (mut entity_instance, mut physical, mut self_physical, mut collider, mut sprite_zone) = match players.get_mut(player) {
+        Ok(player_components) => player_components,
+        _ => return
+    };
+    let reloaded_instance = ldtk_assets.get(ldtk_handle)
+        .and_then(|ldtk_asset| project_entities(ldtk_asset).find(|reloaded| reloaded.iid == entity_instance.iid))
+        .cloned();
+    if let Some(reloaded_instance) = reloaded_instance {
+        physical.weight = Physical::from(reloaded_instance.clone()).weight;
+        self_physical.speed = SelfPhysical::from(reloaded_instance.clone()).speed;
+        *collider = Collider::from(reloaded_instance.clone());
+        *sprite_zone = SpriteZone::from(reloaded_instance.clone());
+        *entity_instance = reloaded_instance;
+    }
+}
@@ -1,13 +1,23 @@
-use bevy::app::Plugin;
+use bevy::app::{App, Plugin};
 use bevy::asset::AssetServer;
-use bevy::prelude::Res;
+use bevy::prelude::{BackgroundColor, BuildChildren, Color, Commands, JustifyContent, NodeBundle, PositionType, Res, Size, Style, TextBundle, TextStyle, UiRect, Val, Visibility};
+use bevy::utils::default;
 use bevy_ecs_ldtk::LdtkWorldBundle;
 use bevy_inspector_egui::{RegisterInspectable, WorldInspectorPlugin};
 use bevy_pixel_camera::PixelCameraBundle;
-use crate::{App, Commands, default};
+use crate::modules::building::components::BuildMenuText;
+use crate::modules::crafting::components::CraftingMenuText;
+use crate::modules::crafting::consts::MENU_FONT_SIZE;
+use crate::modules::interaction::components::InteractPrompt;
+use crate::modules::interaction::consts::{PROMPT_FONT_SIZE, UI_FONT_PATH};
+use crate::modules::level::components::FadeOverlay;
+use crate::modules::living::components::{Health, Stamina, StaminaBar};
+use crate::modules::living::consts::{STAMINA_BAR_HEIGHT, STAMINA_BAR_WIDTH};
 use crate::modules::physics::components::{Collider, Physical, SelfPhysical};
 use crate::modules::physics::sprite_change::components::{MultipleMovementState, MultipleSided, SpriteZone};
 use crate::modules::simple::components::Name;
+use crate::modules::tech::components::NotificationText;
+use crate::modules::tech::consts::NOTIFICATION_FONT_SIZE;
 
 pub struct DebugPlugin;
 
@@ -17,6 +27,8 @@ impl Plugin for DebugPlugin {
             .register_inspectable::<Name>()
             .register_inspectable::<Physical>()
             .register_inspectable::<SelfPhysical>()
+            .register_inspectable::<Health>()
+            .register_inspectable::<Stamina>()
             .register_inspectable::<Collider>()
             .register_inspectable::<SpriteZone>()
             .register_inspectable::<MultipleSided>()
@@ -25,7 +37,7 @@ impl Plugin for DebugPlugin {
 }
 
 pub fn setup_game(mut commands: Commands, asset_server: Res<AssetServer>) {
-    setup_view(&mut commands);
+    setup_view(&mut commands, &asset_server);
     setup_tilemap(&mut commands, &asset_server);
 }
 
@@ -36,6 +48,109 @@ fn setup_tilemap(commands: &mut Commands, asset_server: &Res<AssetServer>) {
     });
 }
 
-fn setup_view(commands: &mut Commands) {
+fn setup_view(commands: &mut Commands, asset_server: &Res<AssetServer>) {
     commands.spawn(PixelCameraBundle::from_resolution(192, 108));
+    commands.spawn(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(STAMINA_BAR_WIDTH), Val::Px(STAMINA_BAR_HEIGHT)),
+            position_type: PositionType::Absolute,
+            position: UiRect { left: Val::Px(8.), bottom: Val::Px(8.), ..default() },
+            ..default()
+        },
+        background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.5)),
+        ..default()
+    }).with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    ..default()
+                },
+                ..default()
+            },
+            StaminaBar
+        ));
+    });
+    commands.spawn((
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect { left: Val::Px(8.), bottom: Val::Px(8. + STAMINA_BAR_HEIGHT + 6.), ..default() },
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..TextBundle::from_section("", TextStyle {
+                font: asset_server.load(UI_FONT_PATH),
+                font_size: PROMPT_FONT_SIZE,
+                color: Color::WHITE,
+            })
+        },
+        InteractPrompt
+    ));
+    commands.spawn((
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect { left: Val::Px(8.), top: Val::Px(8.), ..default() },
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..TextBundle::from_section("", TextStyle {
+                font: asset_server.load(UI_FONT_PATH),
+                font_size: MENU_FONT_SIZE,
+                color: Color::WHITE,
+            })
+        },
+        CraftingMenuText
+    ));
+    commands.spawn((
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect { left: Val::Px(8.), top: Val::Px(8.), ..default() },
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..TextBundle::from_section("", TextStyle {
+                font: asset_server.load(UI_FONT_PATH),
+                font_size: MENU_FONT_SIZE,
+                color: Color::WHITE,
+            })
+        },
+        BuildMenuText
+    ));
+    commands.spawn(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.), Val::Auto),
+            position_type: PositionType::Absolute,
+            position: UiRect { top: Val::Px(8.), ..default() },
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        ..default()
+    }).with_children(|parent| {
+        parent.spawn((
+            TextBundle {
+                visibility: Visibility { is_visible: false },
+                ..TextBundle::from_section("", TextStyle {
+                    font: asset_server.load(UI_FONT_PATH),
+                    font_size: NOTIFICATION_FONT_SIZE,
+                    color: Color::WHITE,
+                })
+            },
+            NotificationText
+        ));
+    });
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::NONE),
+            ..default()
+        },
+        FadeOverlay
+    ));
 }
\ No newline at end of file
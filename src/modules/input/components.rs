@@ -0,0 +1,22 @@
+use std::collections::BTreeSet;
+use bevy::ecs::prelude::Resource;
+
+// menus that take the player's attention, the world stands still while any is open
+#[derive(Default, Resource)]
+pub struct OpenMenus {
+    pub menus: BTreeSet<&'static str>,
+}
+
+impl OpenMenus {
+    pub fn set(&mut self, menu: &'static str, open: bool) {
+        if open {
+            self.menus.insert(menu);
+        } else {
+            self.menus.remove(menu);
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        !self.menus.is_empty()
+    }
+}
@@ -1,10 +1,15 @@
 use bevy::input::Input;
 use bevy::math::Vec3;
 use bevy::prelude::{KeyCode, Query, Res, With};
-use crate::modules::living::components::Player;
+use crate::modules::level::components::LevelTransition;
+use crate::modules::living::components::{Player, Stamina};
+use crate::modules::living::consts::{EXHAUSTED_MULTIPLIER, SPRINT_MULTIPLIER};
 use crate::modules::physics::components::SelfPhysical;
 
-pub fn read_input(keyboard_input: Res<Input<KeyCode>>, mut player_physics: Query<&mut SelfPhysical, With<Player>>) {
+pub fn read_input(
+    keyboard_input: Res<Input<KeyCode>>, transition: Res<LevelTransition>,
+    mut player_physics: Query<(&mut SelfPhysical, Option<&Stamina>), With<Player>>
+) {
     let mut directions = Vec3::ZERO;
     let sprint = keyboard_input.pressed(KeyCode::LShift);
 
@@ -20,14 +25,20 @@ pub fn read_input(keyboard_input: Res<Input<KeyCode>>, mut player_physics: Query
     if keyboard_input.pressed(KeyCode::A) {
         directions.x -= 1.;
     }
+    if transition.is_active() {
+        directions = Vec3::ZERO;
+    }
 
-    for mut physics in player_physics.iter_mut() {
+    for (mut physics, stamina) in player_physics.iter_mut() {
         if physics.direction != directions {
             physics.direction = directions;
         }
         let mut multiplier = 1.;
-        if sprint {
-            multiplier = 1.8;
+        match stamina {
+            Some(stamina) if stamina.exhausted => multiplier = EXHAUSTED_MULTIPLIER,
+            Some(stamina) if sprint && stamina.can_sprint() => multiplier = SPRINT_MULTIPLIER,
+            None if sprint => multiplier = SPRINT_MULTIPLIER,
+            _ => {}
         }
         if multiplier != physics.multiplier {
             physics.multiplier = multiplier;
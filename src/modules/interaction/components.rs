@@ -0,0 +1,46 @@
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::prelude::Entity;
+use bevy::utils::default;
+use bevy_ecs_ldtk::EntityInstance;
+use crate::modules::interaction::consts::{DEFAULT_INTERACT_PROMPT, DEFAULT_INTERACT_RANGE};
+use crate::modules::level::consts::{INTERACT_PROMPT_FIELD, INTERACT_RANGE_FIELD};
+use crate::modules::level::fields::{entity_float, entity_string};
+
+#[derive(Component)]
+pub struct Interactable {
+    pub prompt: String,
+    pub range: f32,
+    pub enabled: bool,
+}
+
+impl Default for Interactable {
+    fn default() -> Self {
+        Interactable { prompt: DEFAULT_INTERACT_PROMPT.to_string(), range: DEFAULT_INTERACT_RANGE, enabled: true }
+    }
+}
+
+impl From<EntityInstance> for Interactable {
+    fn from(entity_instance: EntityInstance) -> Interactable {
+        let interactable = Interactable {..default()};
+        Interactable {
+            prompt: entity_string(&entity_instance, INTERACT_PROMPT_FIELD).unwrap_or(interactable.prompt),
+            range: entity_float(&entity_instance, INTERACT_RANGE_FIELD).unwrap_or(interactable.range),
+            ..interactable
+        }
+    }
+}
+
+// the interactable the player would use when pressing the interact key
+#[derive(Default, Resource)]
+pub struct InteractionFocus {
+    pub target: Option<Entity>,
+}
+
+pub struct Interacted {
+    pub actor: Entity,
+    pub target: Entity,
+}
+
+#[derive(Default, Component)]
+pub struct InteractPrompt;
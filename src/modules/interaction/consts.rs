@@ -0,0 +1,13 @@
+use bevy::prelude::KeyCode;
+
+pub const INTERACT_KEY: KeyCode = KeyCode::E;
+
+pub const DEFAULT_INTERACT_RANGE: f32 = 24.;
+pub const DEFAULT_INTERACT_PROMPT: &str = "Inspect";
+// cosine of the widest angle from the player's facing that still counts as in front
+pub const FACING_MIN_DOT: f32 = 0.3;
+// anything this close is reachable whichever way the player faces
+pub const TOUCH_DISTANCE: f32 = 6.;
+
+pub const UI_FONT_PATH: &str = "fonts/DejaVuSans.ttf";
+pub const PROMPT_FONT_SIZE: f32 = 14.;
@@ -0,0 +1,64 @@
+use bevy::input::Input;
+use bevy::prelude::{Entity, EventWriter, GlobalTransform, KeyCode, Query, Res, ResMut, Text, Visibility, With};
+use crate::modules::interaction::components::{InteractPrompt, Interactable, Interacted, InteractionFocus};
+use crate::modules::interaction::consts::{FACING_MIN_DOT, INTERACT_KEY, TOUCH_DISTANCE};
+use crate::modules::level::components::LevelTransition;
+use crate::modules::living::components::Player;
+use crate::modules::physics::sprite_change::components::MultipleSided;
+
+pub fn update_interaction_focus(
+    mut focus: ResMut<InteractionFocus>, transition: Res<LevelTransition>,
+    players: Query<(Entity, &GlobalTransform, &MultipleSided), With<Player>>,
+    interactables: Query<(Entity, &Interactable, &GlobalTransform)>
+) {
+    let target = match players.get_single() {
+        Ok((player, transform, multiple_sided)) if !transition.is_active() => {
+            let position = transform.translation().truncate();
+            let facing = multiple_sided.side.direction();
+            interactables.iter()
+                .filter(|(entity, interactable, _)| *entity != player && interactable.enabled)
+                .map(|(entity, interactable, transform)| (entity, interactable, transform.translation().truncate() - position))
+                .filter(|(_, interactable, offset)| {
+                    let distance = offset.length();
+                    distance <= interactable.range &&
+                        (distance <= TOUCH_DISTANCE || offset.normalize_or_zero().dot(facing) >= FACING_MIN_DOT)
+                })
+                .min_by(|(_, _, a), (_, _, b)| a.length().total_cmp(&b.length()))
+                .map(|(entity, _, _)| entity)
+        }
+        _ => None
+    };
+    if focus.target != target {
+        focus.target = target;
+    }
+}
+
+pub fn read_interact_input(
+    keyboard_input: Res<Input<KeyCode>>, focus: Res<InteractionFocus>,
+    players: Query<Entity, With<Player>>, mut interacted: EventWriter<Interacted>
+) {
+    if !keyboard_input.just_pressed(INTERACT_KEY) {
+        return;
+    }
+    if let (Ok(actor), Some(target)) = (players.get_single(), focus.target) {
+        interacted.send(Interacted { actor, target });
+    }
+}
+
+pub fn update_interact_prompt(
+    focus: Res<InteractionFocus>, interactables: Query<&Interactable>,
+    mut prompts: Query<(&mut Text, &mut Visibility), With<InteractPrompt>>
+) {
+    let interactable = focus.target.and_then(|target| interactables.get(target).ok());
+    for (mut text, mut visibility) in prompts.iter_mut() {
+        if visibility.is_visible != interactable.is_some() {
+            visibility.is_visible = interactable.is_some();
+        }
+        if let Some(interactable) = interactable {
+            let value = format!("[{:?}] {}", INTERACT_KEY, interactable.prompt);
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        }
+    }
+}
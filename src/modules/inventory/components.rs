@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, SpriteSheetBundle, TimerMode};
+use bevy::sprite::TextureAtlas;
+use bevy::time::Timer;
+use bevy::utils::default;
+use serde::{Deserialize, Serialize};
+use crate::modules::gathering::components::ToolKind;
+use crate::modules::inventory::consts::{ITEM_DATA_PATH, ITEM_ICONS_PATH, ITEM_ICON_COLUMNS, ITEM_ICON_ROWS, ITEM_ICON_SIZE, PICKUP_DELAY_SECONDS, PLAYER_INVENTORY_SLOTS};
+use crate::modules::physics::sprite_change::components::SpriteZone;
+use crate::modules::storage::files::read_ron;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+pub enum ItemCategory { #[default] RESOURCE, FOOD, TOOL, PLACEABLE, ARTIFACT }
+
+#[derive(Clone, Deserialize)]
+pub struct ItemDefinition {
+    pub name: String,
+    pub icon: usize,
+    pub stack_size: u32,
+    pub category: ItemCategory,
+    pub era: u32,
+    // (kind, power) of tools
+    #[serde(default)]
+    pub tool: Option<(ToolKind, f32)>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ItemData {
+    pub items: HashMap<String, ItemDefinition>,
+}
+
+#[derive(Default, Resource)]
+pub struct ItemDatabase {
+    pub data: ItemData,
+    pub icons: Option<Handle<TextureAtlas>>,
+}
+
+impl ItemDatabase {
+    pub fn load() -> Self {
+        let data = read_ron(ITEM_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load items from {}", ITEM_DATA_PATH);
+            ItemData {..default()}
+        });
+        ItemDatabase { data, ..default() }
+    }
+
+    pub fn get(&self, item: &str) -> Option<&ItemDefinition> {
+        self.data.items.get(item)
+    }
+
+    pub fn stack_size(&self, item: &str) -> u32 {
+        self.get(item).map_or(1, |definition| definition.stack_size.max(1))
+    }
+
+    pub fn icons(&mut self, asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>) -> Handle<TextureAtlas> {
+        self.icons.get_or_insert_with(|| {
+            texture_atlases.add(TextureAtlas::from_grid(
+                asset_server.load(ITEM_ICONS_PATH), Vec2::splat(ITEM_ICON_SIZE), ITEM_ICON_COLUMNS, ITEM_ICON_ROWS, None, None
+            ))
+        }).clone()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u32,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+}
+
+impl Inventory {
+    pub fn with_slots(slots: usize) -> Self {
+        Inventory { slots: vec![None; slots] }
+    }
+
+    pub fn count(&self, item: &str) -> u32 {
+        self.slots.iter().flatten().filter(|stack| stack.item == item).map(|stack| stack.count).sum()
+    }
+
+    pub fn has(&self, item: &str, count: u32) -> bool {
+        self.count(item) >= count
+    }
+
+    // fills existing stacks before empty slots, returns what didn't fit
+    pub fn add(&mut self, item: &str, count: u32, items: &ItemDatabase) -> u32 {
+        let stack_size = items.stack_size(item);
+        let mut left = count;
+        for stack in self.slots.iter_mut().flatten().filter(|stack| stack.item == item) {
+            let moved = left.min(stack_size.saturating_sub(stack.count));
+            stack.count += moved;
+            left -= moved;
+        }
+        for slot in self.slots.iter_mut().filter(|slot| slot.is_none()) {
+            if left == 0 {
+                break;
+            }
+            let moved = left.min(stack_size);
+            *slot = Some(ItemStack { item: item.to_string(), count: moved });
+            left -= moved;
+        }
+        left
+    }
+
+    // all or nothing, the last stacks are used up first
+    pub fn remove(&mut self, item: &str, count: u32) -> bool {
+        if !self.has(item, count) {
+            return false;
+        }
+        let mut left = count;
+        for slot in self.slots.iter_mut().rev() {
+            if left == 0 {
+                break;
+            }
+            if let Some(stack) = slot.as_mut().filter(|stack| stack.item == item) {
+                let taken = left.min(stack.count);
+                stack.count -= taken;
+                left -= taken;
+                if stack.count == 0 {
+                    *slot = None;
+                }
+            }
+        }
+        true
+    }
+
+    // moves as much as fits into the other inventory, returns how much moved
+    pub fn transfer(&mut self, other: &mut Inventory, item: &str, count: u32, items: &ItemDatabase) -> u32 {
+        let count = count.min(self.count(item));
+        let moved = count - other.add(item, count, items);
+        self.remove(item, moved);
+        moved
+    }
+
+    // saves from before the inventory may hold fewer slots than it has now
+    pub fn restore(&mut self, saved: Inventory) {
+        let capacity = self.slots.len().max(saved.slots.len());
+        self.slots = saved.slots;
+        self.slots.resize(capacity, None);
+    }
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory::with_slots(PLAYER_INVENTORY_SLOTS)
+    }
+}
+
+// sent by whatever changes an inventory, with a negative delta for removed items
+pub struct InventoryChanged {
+    pub entity: Entity,
+    pub item: String,
+    pub delta: i64,
+}
+
+#[derive(Component)]
+pub struct GroundItem {
+    pub stack: ItemStack,
+    pub delay: Timer,
+}
+
+impl GroundItem {
+    pub fn new(stack: ItemStack) -> Self {
+        GroundItem { stack, delay: Timer::from_seconds(PICKUP_DELAY_SECONDS, TimerMode::Once) }
+    }
+}
+
+#[derive(Bundle)]
+pub struct GroundItemBundle {
+    pub ground_item: GroundItem,
+    pub sprite_zone: SpriteZone,
+
+    #[bundle]
+    pub sprite: SpriteSheetBundle,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> ItemDatabase {
+        let definition = |name: &str, stack_size: u32| ItemDefinition {
+            name: name.to_string(), icon: 0, stack_size, category: ItemCategory::RESOURCE, era: 0, tool: None
+        };
+        let items = HashMap::from([
+            ("stone".to_string(), definition("Stone", 5)),
+            ("stone_axe".to_string(), definition("Stone Axe", 1)),
+        ]);
+        ItemDatabase { data: ItemData { items }, ..default() }
+    }
+
+    fn stack(item: &str, count: u32) -> Option<ItemStack> {
+        Some(ItemStack { item: item.to_string(), count })
+    }
+
+    #[test]
+    fn add_fills_stacks_before_empty_slots() {
+        let items = items();
+        let mut inventory = Inventory { slots: vec![None, stack("stone", 3), None] };
+        assert_eq!(inventory.add("stone", 4, &items), 0);
+        assert_eq!(inventory.slots, vec![stack("stone", 2), stack("stone", 5), None]);
+        assert_eq!(inventory.add("stone_axe", 1, &items), 0);
+        assert_eq!(inventory.slots[2], stack("stone_axe", 1));
+    }
+
+    #[test]
+    fn add_returns_what_does_not_fit() {
+        let items = items();
+        let mut inventory = Inventory::with_slots(2);
+        assert_eq!(inventory.add("stone", 12, &items), 2);
+        assert_eq!(inventory.count("stone"), 10);
+        assert_eq!(inventory.add("stone_axe", 1, &items), 1);
+        assert_eq!(inventory.count("stone_axe"), 0);
+    }
+
+    #[test]
+    fn remove_is_all_or_nothing() {
+        let mut inventory = Inventory { slots: vec![stack("stone", 5), stack("stone", 2), None] };
+        assert!(!inventory.remove("stone", 8));
+        assert_eq!(inventory.count("stone"), 7);
+        assert!(inventory.remove("stone", 3));
+        assert_eq!(inventory.slots, vec![stack("stone", 4), None, None]);
+        assert!(!inventory.remove("stone_axe", 1));
+        assert!(inventory.remove("stone", 4));
+        assert!(inventory.slots.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn transfer_moves_only_what_fits() {
+        let items = items();
+        let mut from = Inventory { slots: vec![stack("stone", 5), stack("stone", 4)] };
+        let mut to = Inventory { slots: vec![stack("stone", 3), None] };
+        assert_eq!(from.transfer(&mut to, "stone", 9, &items), 7);
+        assert_eq!(from.count("stone"), 2);
+        assert_eq!(to.slots, vec![stack("stone", 5), stack("stone", 5)]);
+
+        assert_eq!(from.transfer(&mut to, "stone", 2, &items), 0);
+        assert_eq!(from.count("stone"), 2);
+    }
+
+    #[test]
+    fn transfer_never_moves_more_than_is_held() {
+        let items = items();
+        let mut from = Inventory { slots: vec![stack("stone", 2)] };
+        let mut to = Inventory::with_slots(2);
+        assert_eq!(from.transfer(&mut to, "stone", 6, &items), 2);
+        assert_eq!(from.count("stone"), 0);
+        assert_eq!(to.count("stone"), 2);
+        assert_eq!(from.transfer(&mut to, "stone_axe", 1, &items), 0);
+        assert_eq!(to.count("stone_axe"), 0);
+    }
+}
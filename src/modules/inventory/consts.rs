@@ -0,0 +1,17 @@
+pub const ITEM_DATA_PATH: &str = "assets/data/items.ron";
+
+pub const ITEM_ICONS_PATH: &str = "sprites/items/texture.png";
+pub const ITEM_ICON_SIZE: f32 = 16.;
+pub const ITEM_ICON_COLUMNS: usize = 8;
+pub const ITEM_ICON_ROWS: usize = 4;
+
+pub const PLAYER_INVENTORY_SLOTS: usize = 16;
+
+pub const GROUND_ITEM_Z: f32 = 3.;
+pub const GROUND_ITEM_SCALE: f32 = 0.75;
+// dropped items land this far around where they came from
+pub const DROP_SCATTER: f32 = 10.;
+// fresh drops wait a moment so the player sees them land
+pub const PICKUP_DELAY_SECONDS: f32 = 0.5;
+pub const PICKUP_DISTANCE: f32 = 12.;
+pub const PICKUP_SOUND: &str = "ui/pickup";
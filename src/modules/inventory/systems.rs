@@ -0,0 +1,135 @@
+use bevy::asset::{AssetServer, Assets};
+use bevy::hierarchy::{BuildChildren, Parent};
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::{Changed, Commands, DespawnRecursiveExt, Entity, EventReader, EventWriter, GlobalTransform, Query, Res, ResMut, SpriteSheetBundle, TextureAtlasSprite, Time, Transform, With};
+use bevy::sprite::TextureAtlas;
+use bevy::utils::default;
+use rand::Rng;
+use crate::modules::animal::components::{Animal, AnimalRegistry};
+use crate::modules::gathering::components::{GatherEvent, Tools, roll_drops};
+use crate::modules::inventory::components::{GroundItem, GroundItemBundle, Inventory, InventoryChanged, ItemDatabase, ItemStack};
+use crate::modules::inventory::consts::{DROP_SCATTER, GROUND_ITEM_SCALE, GROUND_ITEM_Z, ITEM_ICON_SIZE, PICKUP_DISTANCE, PICKUP_SOUND};
+use crate::modules::living::components::{DeathEvent, Player};
+use crate::modules::physics::components::TransformZone;
+use crate::modules::physics::sprite_change::components::SpriteZone;
+use crate::modules::rng::components::{RngResource, RngStream};
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
+
+// drops are parented to the level of their source so they unload with it
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_ground_items(
+    commands: &mut Commands, items: &mut ItemDatabase, asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>, rng: &mut RngResource, drops: &[(String, u32)],
+    position: Vec2, level: Option<(Entity, Vec2)>
+) {
+    let icons = items.icons(asset_server, texture_atlases);
+    let size = Vec2::splat(ITEM_ICON_SIZE * GROUND_ITEM_SCALE);
+    for (item, count) in drops {
+        let icon = match items.get(item) {
+            Some(definition) => definition.icon,
+            None => continue
+        };
+        let rng = rng.stream(RngStream::LOOT);
+        let scatter = Vec2::new(rng.gen_range(-1. ..1.), rng.gen_range(-1. ..1.)) * DROP_SCATTER;
+        let local = position + scatter - level.map_or(Vec2::ZERO, |(_, origin)| origin);
+        let ground_item = commands.spawn(GroundItemBundle {
+            ground_item: GroundItem::new(ItemStack { item: item.clone(), count: *count }),
+            sprite_zone: SpriteZone(TransformZone { size, offset: -size / 2. }),
+            sprite: SpriteSheetBundle {
+                texture_atlas: icons.clone(),
+                sprite: TextureAtlasSprite::new(icon),
+                transform: Transform::from_xyz(local.x, local.y, GROUND_ITEM_Z).with_scale(Vec3::splat(GROUND_ITEM_SCALE)),
+                ..default()
+            },
+        }).id();
+        if let Some((level, _)) = level {
+            commands.entity(level).add_child(ground_item);
+        }
+    }
+}
+
+pub fn level_of(entity: Entity, parents: &Query<&Parent>, transforms: &Query<&GlobalTransform>) -> Option<(Entity, Vec2)> {
+    let level = parents.get(entity).ok()?.get();
+    Some((level, transforms.get(level).ok()?.translation().truncate()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn drop_gathered_items(
+    mut commands: Commands, mut gather_events: EventReader<GatherEvent>, mut items: ResMut<ItemDatabase>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>, mut rng: ResMut<RngResource>,
+    parents: Query<&Parent>, transforms: Query<&GlobalTransform>
+) {
+    for gather_event in gather_events.iter() {
+        spawn_ground_items(
+            &mut commands, &mut items, &asset_server, &mut texture_atlases, &mut rng, &gather_event.drops,
+            gather_event.position, level_of(gather_event.node, &parents, &transforms)
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn drop_loot(
+    mut commands: Commands, mut death_events: EventReader<DeathEvent>, mut items: ResMut<ItemDatabase>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>, mut rng: ResMut<RngResource>,
+    registry: Res<AnimalRegistry>, animals: Query<&Animal>, parents: Query<&Parent>, transforms: Query<&GlobalTransform>
+) {
+    for death_event in death_events.iter() {
+        let species = match animals.get(death_event.entity).ok().and_then(|animal| registry.data.species.get(&animal.species)) {
+            Some(species) => species,
+            None => continue
+        };
+        let drops = roll_drops(species.loot_rolls, &species.loot, rng.stream(RngStream::LOOT));
+        spawn_ground_items(
+            &mut commands, &mut items, &asset_server, &mut texture_atlases, &mut rng, &drops,
+            death_event.position, level_of(death_event.entity, &parents, &transforms)
+        );
+    }
+}
+
+pub fn pick_up_items(
+    mut commands: Commands, time: Res<Time>, items: Res<ItemDatabase>,
+    mut players: Query<(Entity, &GlobalTransform, &mut Inventory), With<Player>>,
+    mut ground_items: Query<(Entity, &mut GroundItem, &GlobalTransform)>,
+    mut inventory_events: EventWriter<InventoryChanged>, mut sound_events: EventWriter<SoundEvent>
+) {
+    let (player, player_transform, mut inventory) = match players.get_single_mut() {
+        Ok(player) => player,
+        _ => return
+    };
+    let position = player_transform.translation().truncate();
+    for (entity, mut ground_item, transform) in ground_items.iter_mut() {
+        if !ground_item.delay.tick(time.delta()).finished() ||
+            transform.translation().truncate().distance(position) > PICKUP_DISTANCE {
+            continue;
+        }
+        let left = inventory.add(&ground_item.stack.item, ground_item.stack.count, &items);
+        let taken = ground_item.stack.count - left;
+        if taken == 0 {
+            continue;
+        }
+        inventory_events.send(InventoryChanged { entity: player, item: ground_item.stack.item.clone(), delta: taken as i64 });
+        sound_events.send(SoundEvent {
+            path: PICKUP_SOUND.to_string(),
+            sound_type: SoundType::RANDOM,
+            file: String::new(),
+            channel: SoundChannel::UI,
+            emitter: None,
+        });
+        if left == 0 {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            ground_item.stack.count = left;
+        }
+    }
+}
+
+pub fn update_carried_tools(items: Res<ItemDatabase>, mut holders: Query<(&Inventory, &mut Tools), Changed<Inventory>>) {
+    for (inventory, mut tools) in holders.iter_mut() {
+        let carried: Vec<_> = inventory.slots.iter().flatten()
+            .filter_map(|stack| items.get(&stack.item).and_then(|definition| definition.tool))
+            .collect();
+        if tools.carried != carried {
+            tools.carried = carried;
+        }
+    }
+}
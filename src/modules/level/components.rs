@@ -0,0 +1,154 @@
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::math::Vec2;
+use bevy::prelude::TimerMode;
+use bevy::time::Timer;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity, LevelSelection};
+use crate::modules::level::consts::{DOOR_TARGET_LEVEL_FIELD, DOOR_TARGET_SPAWN_FIELD, TRANSITION_FADE_SECONDS};
+use crate::modules::level::fields::{entity_ref, entity_string};
+
+#[derive(Clone, Debug)]
+pub struct EntityReference {
+    pub entity_iid: String,
+    pub level_iid: String,
+}
+
+#[derive(Default, Component)]
+pub struct Door {
+    pub target_level: Option<String>,
+    pub target_spawn: Option<EntityReference>,
+    pub size: Vec2,
+}
+
+impl Door {
+    pub fn contains(&self, center: Vec2, position: Vec2) -> bool {
+        let distance = (position - center).abs();
+        distance.x <= self.size.x / 2. && distance.y <= self.size.y / 2.
+    }
+
+    pub fn level_selection(&self) -> Option<LevelSelection> {
+        match (&self.target_level, &self.target_spawn) {
+            (Some(identifier), _) => Some(LevelSelection::Identifier(identifier.clone())),
+            (None, Some(spawn)) => Some(LevelSelection::Iid(spawn.level_iid.clone())),
+            (None, None) => None
+        }
+    }
+}
+
+impl From<EntityInstance> for Door {
+    fn from(entity_instance: EntityInstance) -> Door {
+        Door {
+            target_level: entity_string(&entity_instance, DOOR_TARGET_LEVEL_FIELD),
+            target_spawn: entity_ref(&entity_instance, DOOR_TARGET_SPAWN_FIELD),
+            size: Vec2::new(entity_instance.width as f32, entity_instance.height as f32),
+        }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct DoorBundle {
+    #[from_entity_instance]
+    pub door: Door,
+}
+
+#[derive(Default, Component)]
+pub struct Checkpoint {
+    pub size: Vec2,
+}
+
+impl Checkpoint {
+    pub fn contains(&self, center: Vec2, position: Vec2) -> bool {
+        let distance = (position - center).abs();
+        distance.x <= self.size.x / 2. && distance.y <= self.size.y / 2.
+    }
+}
+
+impl From<EntityInstance> for Checkpoint {
+    fn from(entity_instance: EntityInstance) -> Checkpoint {
+        Checkpoint { size: Vec2::new(entity_instance.width as f32, entity_instance.height as f32) }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct CheckpointBundle {
+    #[from_entity_instance]
+    pub checkpoint: Checkpoint,
+}
+
+#[derive(Default, Resource)]
+pub struct LastCheckpoint {
+    pub level: Option<LevelSelection>,
+    pub position: Option<Vec2>,
+}
+
+#[derive(Default, Component)]
+pub struct FadeOverlay;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TransitionState { NONE, CLOSING, LOADING, OPENING }
+
+#[derive(Resource)]
+pub struct LevelTransition {
+    pub state: TransitionState,
+    pub timer: Timer,
+    pub target_level: Option<LevelSelection>,
+    pub target_spawn: Option<EntityReference>,
+    pub target_position: Option<Vec2>,
+    pub respawn_levels: bool,
+    pub awaiting_level: bool,
+    pub autosave: bool,
+    pub doors_armed: bool,
+}
+
+impl LevelTransition {
+    pub fn is_active(&self) -> bool {
+        self.state != TransitionState::NONE
+    }
+
+    pub fn start(&mut self, target_level: LevelSelection, target_spawn: Option<EntityReference>) {
+        self.state = TransitionState::CLOSING;
+        self.timer.reset();
+        self.target_level = Some(target_level);
+        self.target_spawn = target_spawn;
+        self.target_position = None;
+        self.respawn_levels = false;
+        self.autosave = true;
+    }
+
+    pub fn start_load(&mut self, target_level: LevelSelection, target_position: Vec2) {
+        self.state = TransitionState::CLOSING;
+        self.timer.reset();
+        self.target_level = Some(target_level);
+        self.target_spawn = None;
+        self.target_position = Some(target_position);
+        self.respawn_levels = true;
+        self.autosave = false;
+    }
+
+    pub fn start_respawn(&mut self, target_level: Option<LevelSelection>, target_position: Vec2) {
+        self.state = TransitionState::CLOSING;
+        self.timer.reset();
+        self.target_level = target_level;
+        self.target_spawn = None;
+        self.target_position = Some(target_position);
+        self.respawn_levels = false;
+        self.autosave = false;
+    }
+}
+
+impl Default for LevelTransition {
+    fn default() -> Self {
+        LevelTransition {
+            state: TransitionState::NONE,
+            timer: Timer::from_seconds(TRANSITION_FADE_SECONDS, TimerMode::Once),
+            target_level: None,
+            target_spawn: None,
+            target_position: None,
+            respawn_levels: false,
+            awaiting_level: false,
+            autosave: false,
+            doors_armed: true,
+        }
+    }
+}
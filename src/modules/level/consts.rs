@@ -0,0 +1,49 @@
+pub const PLAYER_IDENTIFIER: &str = "Player";
+pub const TREE_STUMP_IDENTIFIER: &str = "Tree_Stump";
+pub const ROCK_IDENTIFIER: &str = "Rock";
+pub const MUSIC_ZONE_IDENTIFIER: &str = "Music_Zone";
+pub const DOOR_IDENTIFIER: &str = "Door";
+pub const BUSH_IDENTIFIER: &str = "Bush";
+pub const FLOWER_IDENTIFIER: &str = "Flower";
+pub const CHECKPOINT_IDENTIFIER: &str = "Checkpoint";
+pub const VILLAGER_IDENTIFIER: &str = "Villager";
+pub const ANIMAL_IDENTIFIER: &str = "Animal";
+pub const WORKSTATION_IDENTIFIER: &str = "Workstation";
+
+pub const COLLIDER_ENTITIES: &[&str] = &[
+    PLAYER_IDENTIFIER, TREE_STUMP_IDENTIFIER, ROCK_IDENTIFIER, BUSH_IDENTIFIER, VILLAGER_IDENTIFIER
+];
+
+pub const SPEED_FIELD: &str = "Speed";
+pub const WEIGHT_FIELD: &str = "Weight";
+pub const HEALTH_FIELD: &str = "Health";
+pub const STAMINA_FIELD: &str = "Stamina";
+pub const COLLIDER_FIELD_PREFIX: &str = "Collider";
+pub const SPRITE_ZONE_FIELD_PREFIX: &str = "Sprite_Zone";
+pub const PROCEDURAL_FIELD: &str = "Procedural";
+pub const WANDER_RADIUS_FIELD: &str = "Wander_Radius";
+pub const SCHEDULE_FIELD: &str = "Schedule";
+pub const SCHEDULE_HOURS_FIELD: &str = "Schedule_Hours";
+pub const PLAYER_REACTION_FIELD: &str = "Player_Reaction";
+pub const REACTION_DISTANCE_FIELD: &str = "Reaction_Distance";
+pub const BRAIN_FIELD: &str = "Brain";
+pub const SIGHT_DISTANCE_FIELD: &str = "Sight_Distance";
+pub const SIGHT_ANGLE_FIELD: &str = "Sight_Angle";
+pub const HEARING_DISTANCE_FIELD: &str = "Hearing_Distance";
+pub const SPECIES_FIELD: &str = "Species";
+pub const SPAWN_TABLE_FIELD: &str = "Spawn_Table";
+pub const STATION_FIELD: &str = "Station";
+pub const INTERACT_PROMPT_FIELD: &str = "Interact_Prompt";
+pub const INTERACT_RANGE_FIELD: &str = "Interact_Range";
+
+pub const LEVEL_GRID_SIZE: f32 = 16.;
+pub const ENTITY_LAYER_IDENTIFIER: &str = "Entities";
+pub const COLLISION_LAYER_IDENTIFIER: &str = "Collision";
+pub const TILE_LAYER_IDENTIFIER: &str = "Tiles";
+// the game starts here however the levels are ordered in LDtk
+pub const START_LEVEL_IDENTIFIER: &str = "Level_0";
+
+pub const DOOR_TARGET_LEVEL_FIELD: &str = "Target_Level";
+pub const DOOR_TARGET_SPAWN_FIELD: &str = "Target_Spawn";
+
+pub const TRANSITION_FADE_SECONDS: f32 = 0.4;
@@ -0,0 +1,68 @@
+use bevy::app::App;
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::bundle::Bundle;
+use bevy::prelude::Image;
+use bevy::sprite::TextureAtlas;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::app::{LdtkEntity, RegisterLdtkObjects};
+use bevy_ecs_ldtk::ldtk::{LayerInstance, TilesetDefinition};
+use crate::modules::animal::components::AnimalSpawnBundle;
+use crate::modules::crafting::components::WorkstationSpawnBundle;
+use crate::modules::level::components::{CheckpointBundle, DoorBundle};
+use crate::modules::level::consts::{ANIMAL_IDENTIFIER, BUSH_IDENTIFIER, CHECKPOINT_IDENTIFIER, DOOR_IDENTIFIER, FLOWER_IDENTIFIER, MUSIC_ZONE_IDENTIFIER, PLAYER_IDENTIFIER, ROCK_IDENTIFIER, TREE_STUMP_IDENTIFIER, VILLAGER_IDENTIFIER, WORKSTATION_IDENTIFIER};
+use crate::modules::living::components::PlayerBundle;
+use crate::modules::music::components::MusicZoneBundle;
+use crate::modules::npc::components::VillagerBundle;
+use crate::modules::prop::components::{BushBundle, FloraBundle, RockBundle, TreeStumpBundle};
+
+// registered entities keep their EntityInstance like unregistered ones do, systems look them up by iid and fields
+#[derive(Bundle)]
+pub struct LevelEntityBundle<B: Bundle> {
+    pub entity_instance: EntityInstance,
+    #[bundle]
+    pub bundle: B,
+}
+
+impl<B: LdtkEntity + Bundle> LdtkEntity for LevelEntityBundle<B> {
+    fn bundle_entity(
+        entity_instance: &EntityInstance, layer_instance: &LayerInstance, tileset: Option<&Handle<Image>>,
+        tileset_definition: Option<&TilesetDefinition>, asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>
+    ) -> Self {
+        LevelEntityBundle {
+            entity_instance: entity_instance.clone(),
+            bundle: B::bundle_entity(entity_instance, layer_instance, tileset, tileset_definition, asset_server, texture_atlases),
+        }
+    }
+}
+
+// the validator's list and the app's registrations come from the same table so they can't drift apart
+macro_rules! level_entities {
+    ($($identifier:expr => $bundle:ty),* $(,)?) => {
+        pub const REGISTERED_ENTITIES: &[&str] = &[$($identifier),*];
+
+        pub trait RegisterLevelEntities {
+            fn register_level_entities(&mut self) -> &mut Self;
+        }
+
+        impl RegisterLevelEntities for App {
+            fn register_level_entities(&mut self) -> &mut Self {
+                $(self.register_ldtk_entity::<LevelEntityBundle<$bundle>>($identifier);)*
+                self
+            }
+        }
+    };
+}
+
+level_entities! {
+    PLAYER_IDENTIFIER => PlayerBundle,
+    TREE_STUMP_IDENTIFIER => TreeStumpBundle,
+    ROCK_IDENTIFIER => RockBundle,
+    BUSH_IDENTIFIER => BushBundle,
+    FLOWER_IDENTIFIER => FloraBundle,
+    MUSIC_ZONE_IDENTIFIER => MusicZoneBundle,
+    DOOR_IDENTIFIER => DoorBundle,
+    CHECKPOINT_IDENTIFIER => CheckpointBundle,
+    VILLAGER_IDENTIFIER => VillagerBundle,
+    ANIMAL_IDENTIFIER => AnimalSpawnBundle,
+    WORKSTATION_IDENTIFIER => WorkstationSpawnBundle,
+}
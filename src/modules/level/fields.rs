@@ -0,0 +1,140 @@
+use bevy::log::warn;
+use bevy::math::IVec2;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::ldtk::{EntityDefinition, FieldInstance, FieldValue};
+use serde_json::{json, Value};
+use crate::modules::level::components::EntityReference;
+
+fn find_field<'a>(field_instances: &'a [FieldInstance], identifier: &str) -> Option<&'a FieldValue> {
+    field_instances.iter().find(|field| field.identifier == identifier).map(|field| &field.value)
+}
+
+fn warn_wrong_type(owner: &str, identifier: &str, expected: &str, value: &FieldValue) {
+    warn!("Field {} of {} should be {}, found {:?}; using the default", identifier, owner, expected, value);
+}
+
+pub fn float_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Option<f32> {
+    match find_field(field_instances, identifier)? {
+        FieldValue::Float(value) => *value,
+        FieldValue::Int(value) => value.map(|value| value as f32),
+        value => {
+            warn_wrong_type(owner, identifier, "a Float", value);
+            None
+        }
+    }
+}
+
+pub fn bool_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Option<bool> {
+    match find_field(field_instances, identifier)? {
+        FieldValue::Bool(value) => Some(*value),
+        value => {
+            warn_wrong_type(owner, identifier, "a Bool", value);
+            None
+        }
+    }
+}
+
+pub fn string_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Option<String> {
+    match find_field(field_instances, identifier)? {
+        FieldValue::String(value) | FieldValue::Enum(value) => value.clone(),
+        value => {
+            warn_wrong_type(owner, identifier, "a String", value);
+            None
+        }
+    }
+}
+
+pub fn strings_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Vec<String> {
+    match find_field(field_instances, identifier) {
+        Some(FieldValue::Strings(values)) => values.iter().flatten().cloned().collect(),
+        Some(value) => {
+            warn_wrong_type(owner, identifier, "an Array<String>", value);
+            Vec::new()
+        }
+        None => Vec::new()
+    }
+}
+
+pub fn floats_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Vec<f32> {
+    match find_field(field_instances, identifier) {
+        Some(FieldValue::Floats(values)) => values.iter().flatten().copied().collect(),
+        Some(FieldValue::Ints(values)) => values.iter().flatten().map(|value| *value as f32).collect(),
+        Some(value) => {
+            warn_wrong_type(owner, identifier, "an Array<Float>", value);
+            Vec::new()
+        }
+        None => Vec::new()
+    }
+}
+
+pub fn entity_ref_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Option<EntityReference> {
+    match find_field(field_instances, identifier)? {
+        FieldValue::EntityRef(reference) => reference.as_ref().map(|reference| EntityReference {
+            entity_iid: reference.entity_iid.clone(),
+            level_iid: reference.level_iid.clone(),
+        }),
+        value => {
+            warn_wrong_type(owner, identifier, "an Entity ref", value);
+            None
+        }
+    }
+}
+
+pub fn points_field(owner: &str, field_instances: &[FieldInstance], identifier: &str) -> Vec<IVec2> {
+    match find_field(field_instances, identifier) {
+        Some(FieldValue::Points(points)) => points.iter().flatten().copied().collect(),
+        Some(FieldValue::Point(point)) => point.iter().copied().collect(),
+        Some(value) => {
+            warn_wrong_type(owner, identifier, "an Array<Point>", value);
+            Vec::new()
+        }
+        None => Vec::new()
+    }
+}
+
+pub fn entity_float(entity_instance: &EntityInstance, identifier: &str) -> Option<f32> {
+    float_field(&entity_instance.identifier, &entity_instance.field_instances, identifier)
+}
+
+pub fn entity_string(entity_instance: &EntityInstance, identifier: &str) -> Option<String> {
+    string_field(&entity_instance.identifier, &entity_instance.field_instances, identifier)
+}
+
+pub fn entity_strings(entity_instance: &EntityInstance, identifier: &str) -> Vec<String> {
+    strings_field(&entity_instance.identifier, &entity_instance.field_instances, identifier)
+}
+
+pub fn entity_floats(entity_instance: &EntityInstance, identifier: &str) -> Vec<f32> {
+    floats_field(&entity_instance.identifier, &entity_instance.field_instances, identifier)
+}
+
+pub fn entity_ref(entity_instance: &EntityInstance, identifier: &str) -> Option<EntityReference> {
+    entity_ref_field(&entity_instance.identifier, &entity_instance.field_instances, identifier)
+}
+
+
+pub fn entity_points(entity_instance: &EntityInstance, identifier: &str) -> Vec<IVec2> {
+    points_field(&entity_instance.identifier, &entity_instance.field_instances, identifier)
+}
+
+// what LDtk writes into a new instance of the entity, so entities made outside the editor read the same fields
+pub fn default_field_instances(definition: &EntityDefinition) -> Vec<FieldInstance> {
+    definition.field_defs.iter().filter_map(|field_definition| {
+        let value = field_definition.default_override.as_ref()
+            .and_then(|default_override| default_override.get("params"))
+            .and_then(|params| params.get(0))
+            .cloned()
+            .unwrap_or(match field_definition.field_definition_type.as_str() {
+                "Bool" => Value::Bool(false),
+                _ if field_definition.is_array => Value::Array(Vec::new()),
+                _ => Value::Null
+            });
+        let field_instance = json!({
+            "__identifier": field_definition.identifier, "__type": field_definition.field_definition_type,
+            "__value": value, "__tile": null, "defUid": field_definition.uid, "realEditorValues": []
+        });
+        serde_json::from_value(field_instance).map_err(|error| {
+            warn!("Default of field {} of {} can't be read: {}", field_definition.identifier, definition.identifier, error);
+        }).ok()
+    }).collect()
+}
@@ -0,0 +1,6 @@
+pub mod fields;
+pub mod consts;
+pub mod components;
+pub mod entities;
+pub mod systems;
+pub mod validation;
\ No newline at end of file
@@ -0,0 +1,207 @@
+use bevy::asset::{Assets, Handle};
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::prelude::{Added, BackgroundColor, Color, Commands, DespawnRecursiveExt, Entity, EventReader, EventWriter, GlobalTransform, Query, Res, ResMut, Time, Transform, With};
+use bevy_ecs_ldtk::{EntityInstance, LevelSelection};
+use bevy_ecs_ldtk::ldtk::Level;
+use bevy_ecs_ldtk::prelude::{LdtkAsset, LdtkLevel, LevelEvent, Respawn};
+use crate::modules::level::components::{Checkpoint, Door, FadeOverlay, LastCheckpoint, LevelTransition, TransitionState};
+use crate::modules::living::components::Player;
+use crate::modules::save::components::SaveEvent;
+use crate::modules::save::consts::AUTOSAVE_SLOT;
+
+pub fn enter_doors(
+    mut transition: ResMut<LevelTransition>, players: Query<&GlobalTransform, With<Player>>,
+    doors: Query<(&Door, &GlobalTransform)>
+) {
+    if transition.is_active() {
+        return;
+    }
+    let position = match players.get_single() {
+        Ok(player) => player.translation().truncate(),
+        _ => return
+    };
+    let door = doors.iter()
+        .find(|(door, transform)| door.contains(transform.translation().truncate(), position));
+    match door {
+        Some((door, _)) => {
+            if !transition.doors_armed {
+                return;
+            }
+            transition.doors_armed = false;
+            match door.level_selection() {
+                Some(level_selection) => transition.start(level_selection, door.target_spawn.clone()),
+                None => warn!("Door has neither a target level nor a target spawn")
+            }
+        }
+        None => {
+            if !transition.doors_armed {
+                transition.doors_armed = true;
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_level_transition(
+    mut commands: Commands, mut transition: ResMut<LevelTransition>, mut level_selection: ResMut<LevelSelection>,
+    time: Res<Time>, mut level_events: EventReader<LevelEvent>, mut save_events: EventWriter<SaveEvent>,
+    levels: Query<Entity, With<Handle<LdtkLevel>>>, spawn_points: Query<(&EntityInstance, &GlobalTransform)>,
+    mut players: Query<&mut Transform, With<Player>>, mut overlays: Query<&mut BackgroundColor, With<FadeOverlay>>
+) {
+    let level_transformed = level_events.iter()
+        .filter(|level_event| matches!(level_event, LevelEvent::Transformed(_)))
+        .count() > 0;
+    if !transition.is_active() {
+        return;
+    }
+
+    let mut alpha = 1.;
+    match transition.state {
+        TransitionState::NONE => {}
+        TransitionState::CLOSING => {
+            alpha = transition.timer.tick(time.delta()).percent();
+            if transition.timer.finished() {
+                // a neighbour level may already be streamed in, in which case no spawn event will come
+                let spawn_loaded = transition.target_spawn.as_ref().is_some_and(|target_spawn| {
+                    spawn_points.iter().any(|(entity_instance, _)| entity_instance.iid == target_spawn.entity_iid)
+                });
+                if let Some(target_level) = transition.target_level.take() {
+                    // positions don't depend on the level entities, so there is nothing to wait for
+                    transition.awaiting_level = *level_selection != target_level && !spawn_loaded
+                        && transition.target_position.is_none();
+                    if *level_selection != target_level {
+                        *level_selection = target_level;
+                    }
+                }
+                if transition.respawn_levels {
+                    for level in levels.iter() {
+                        commands.entity(level).insert(Respawn);
+                    }
+                    transition.awaiting_level = true;
+                }
+                transition.state = TransitionState::LOADING;
+            }
+        }
+        TransitionState::LOADING => {
+            if transition.awaiting_level && !level_transformed {
+                return;
+            }
+            if let Some(target_spawn) = transition.target_spawn.take() {
+                let spawn_point = spawn_points.iter()
+                    .find(|(entity_instance, _)| entity_instance.iid == target_spawn.entity_iid);
+                match spawn_point {
+                    Some((_, spawn_transform)) => {
+                        for mut player_transform in players.iter_mut() {
+                            player_transform.translation.x = spawn_transform.translation().x;
+                            player_transform.translation.y = spawn_transform.translation().y;
+                        }
+                    }
+                    None => warn!("Spawn point {} was not found in level {}", target_spawn.entity_iid, target_spawn.level_iid)
+                }
+            }
+            if let Some(target_position) = transition.target_position.take() {
+                for mut player_transform in players.iter_mut() {
+                    player_transform.translation.x = target_position.x;
+                    player_transform.translation.y = target_position.y;
+                }
+            }
+            transition.timer.reset();
+            transition.state = TransitionState::OPENING;
+        }
+        TransitionState::OPENING => {
+            alpha = 1. - transition.timer.tick(time.delta()).percent();
+            if transition.timer.finished() {
+                transition.state = TransitionState::NONE;
+                if transition.autosave {
+                    save_events.send(SaveEvent { slot: AUTOSAVE_SLOT });
+                }
+            }
+        }
+    }
+
+    for mut overlay in overlays.iter_mut() {
+        overlay.0 = Color::rgba(0., 0., 0., alpha);
+    }
+}
+
+pub fn reach_checkpoints(
+    mut last_checkpoint: ResMut<LastCheckpoint>, transition: Res<LevelTransition>, level_selection: Res<LevelSelection>,
+    players: Query<&GlobalTransform, With<Player>>, added_players: Query<&GlobalTransform, Added<Player>>,
+    checkpoints: Query<(&Checkpoint, &GlobalTransform)>
+) {
+    if transition.is_active() {
+        return;
+    }
+    // the first place the player appears at is the checkpoint until a real one is reached
+    if last_checkpoint.position.is_none() {
+        if let Ok(player) = added_players.get_single() {
+            last_checkpoint.level = Some(level_selection.clone());
+            last_checkpoint.position = Some(player.translation().truncate());
+        }
+        return;
+    }
+    let position = match players.get_single() {
+        Ok(player) => player.translation().truncate(),
+        _ => return
+    };
+    let checkpoint = checkpoints.iter()
+        .find(|(checkpoint, transform)| checkpoint.contains(transform.translation().truncate(), position));
+    if let Some((_, transform)) = checkpoint {
+        let checkpoint_position = transform.translation().truncate();
+        if last_checkpoint.position != Some(checkpoint_position) {
+            last_checkpoint.level = Some(level_selection.clone());
+            last_checkpoint.position = Some(checkpoint_position);
+        }
+    }
+}
+
+pub fn despawn_duplicate_players(
+    mut commands: Commands, added_players: Query<Entity, Added<Player>>, players: Query<Entity, With<Player>>
+) {
+    let kept_players = players.iter().filter(|player| !added_players.contains(*player)).count();
+    for (index, player) in added_players.iter().enumerate() {
+        if kept_players > 0 || index > 0 {
+            commands.entity(player).despawn_recursive();
+        }
+    }
+}
+
+pub fn select_level_by_player_position(
+    transition: Res<LevelTransition>, mut level_selection: ResMut<LevelSelection>,
+    ldtk_worlds: Query<&Handle<LdtkAsset>>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    players: Query<&GlobalTransform, With<Player>>
+) {
+    if transition.is_active() {
+        return;
+    }
+    let position = match players.get_single() {
+        Ok(player) => player.translation().truncate(),
+        _ => return
+    };
+    let ldtk_asset = match ldtk_worlds.get_single().ok().and_then(|ldtk_handle| ldtk_assets.get(ldtk_handle)) {
+        Some(ldtk_asset) => ldtk_asset,
+        None => return
+    };
+    let level = ldtk_asset.project.levels.iter().enumerate()
+        .find(|(_, level)| level_contains(level, position));
+    if let Some((index, level)) = level {
+        if !level_selection.is_match(&index, level) {
+            *level_selection = LevelSelection::Iid(level.iid.clone());
+        }
+    }
+}
+
+// LDtk world coordinates grow downwards while bevy's grow upwards
+pub fn level_contains(level: &Level, position: Vec2) -> bool {
+    let min = Vec2::new(level.world_x as f32, -(level.world_y + level.px_hei) as f32);
+    let max = min + Vec2::new(level.px_wid as f32, level.px_hei as f32);
+    position.x >= min.x && position.x < max.x && position.y >= min.y && position.y < max.y
+}
+
+pub fn project_entities(ldtk_asset: &LdtkAsset) -> impl Iterator<Item = &EntityInstance> {
+    ldtk_asset.project.levels.iter()
+        .filter_map(|level| level.layer_instances.as_ref())
+        .flatten()
+        .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+}
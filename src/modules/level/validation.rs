@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use bevy::math::Vec2;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::ldtk::{LdtkJson, Level};
+use crate::modules::level::consts::{COLLIDER_ENTITIES, DOOR_IDENTIFIER, DOOR_TARGET_LEVEL_FIELD, DOOR_TARGET_SPAWN_FIELD, MUSIC_ZONE_IDENTIFIER, PLAYER_IDENTIFIER, START_LEVEL_IDENTIFIER};
+use crate::modules::level::entities::REGISTERED_ENTITIES;
+use crate::modules::level::fields::{entity_ref, entity_string, entity_strings, string_field, strings_field};
+use crate::modules::music::consts::{AMBIENCE_FIELD, AMBIENCE_PATH, MUSIC_FIELD, MUSIC_PATH, NIGHT_AMBIENCE_FIELD};
+use crate::modules::physics::components::Collider;
+
+pub fn find_projects(directory: &Path) -> Vec<PathBuf> {
+    let mut projects = Vec::new();
+    if let Ok(entries) = fs::read_dir(directory) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                projects.extend(find_projects(&path));
+            } else if path.extension().is_some_and(|extension| extension == "ldtk") {
+                projects.push(path);
+            }
+        }
+    }
+    projects.sort();
+    projects
+}
+
+pub fn validate_project(project_path: &Path, assets_directory: &Path) -> Vec<String> {
+    let mut errors = Vec::new();
+    let project: LdtkJson = match fs::read_to_string(project_path).map_err(|error| error.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|error| error.to_string())) {
+        Ok(project) => project,
+        Err(error) => return vec![format!("could not read project: {}", error)]
+    };
+    let project_directory = project_path.parent().unwrap_or(Path::new("."));
+    let levels = load_levels(&project, project_directory, &mut errors);
+
+    for tileset in project.defs.tilesets.iter() {
+        if let Some(rel_path) = &tileset.rel_path {
+            if !project_directory.join(rel_path).exists() {
+                errors.push(format!("tileset {} points to missing sprite {}", tileset.identifier, rel_path));
+            }
+        }
+    }
+
+    match levels.iter().find(|level| level.identifier == START_LEVEL_IDENTIFIER) {
+        Some(start_level) => {
+            let players = level_entities(start_level)
+                .filter(|entity_instance| entity_instance.identifier == PLAYER_IDENTIFIER)
+                .count();
+            if players != 1 {
+                errors.push(format!("start level {} has {} {} entities, expected 1", start_level.identifier, players, PLAYER_IDENTIFIER));
+            }
+        }
+        None => errors.push(format!("project has no start level {}", START_LEVEL_IDENTIFIER))
+    }
+
+    for level in levels.iter() {
+        let sounds = string_field(&level.identifier, &level.field_instances, MUSIC_FIELD).into_iter()
+            .map(|track| (MUSIC_PATH, track))
+            .chain(strings_field(&level.identifier, &level.field_instances, AMBIENCE_FIELD).into_iter()
+                .chain(strings_field(&level.identifier, &level.field_instances, NIGHT_AMBIENCE_FIELD))
+                .map(|ambience| (AMBIENCE_PATH, ambience)));
+        for (directory, sound) in sounds {
+            check_sound(assets_directory, directory, &sound, &level.identifier, &mut errors);
+        }
+
+        for entity_instance in level_entities(level) {
+            let location = format!("{} {} in {}", entity_instance.identifier, entity_instance.iid, level.identifier);
+            if !REGISTERED_ENTITIES.contains(&entity_instance.identifier.as_str()) {
+                errors.push(format!("{} has no registered bundle", location));
+            }
+            if entity_instance.identifier == DOOR_IDENTIFIER {
+                check_door(entity_instance, &levels, &location, &mut errors);
+            }
+            if entity_instance.identifier == MUSIC_ZONE_IDENTIFIER {
+                let sounds = entity_string(entity_instance, MUSIC_FIELD).into_iter()
+                    .map(|track| (MUSIC_PATH, track))
+                    .chain(entity_strings(entity_instance, AMBIENCE_FIELD).into_iter()
+                        .chain(entity_strings(entity_instance, NIGHT_AMBIENCE_FIELD))
+                        .map(|ambience| (AMBIENCE_PATH, ambience)));
+                for (directory, sound) in sounds {
+                    check_sound(assets_directory, directory, &sound, &location, &mut errors);
+                }
+            }
+        }
+
+        check_collider_overlaps(level, &mut errors);
+    }
+    errors
+}
+
+fn load_levels(project: &LdtkJson, project_directory: &Path, errors: &mut Vec<String>) -> Vec<Level> {
+    project.levels.iter().filter_map(|level| {
+        let external_rel_path = match &level.external_rel_path {
+            Some(external_rel_path) => external_rel_path,
+            None => return Some(level.clone())
+        };
+        let external_level = fs::read_to_string(project_directory.join(external_rel_path))
+            .map_err(|error| error.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|error| error.to_string()));
+        match external_level {
+            Ok(external_level) => Some(external_level),
+            Err(error) => {
+                errors.push(format!("could not read level {} from {}: {}", level.identifier, external_rel_path, error));
+                None
+            }
+        }
+    }).collect()
+}
+
+fn level_entities(level: &Level) -> impl Iterator<Item = &EntityInstance> {
+    level.layer_instances.iter()
+        .flatten()
+        .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+}
+
+fn check_sound(assets_directory: &Path, directory: &str, sound: &str, owner: &str, errors: &mut Vec<String>) {
+    let path = assets_directory.join(directory).join(format!("{}.wav", sound));
+    if !path.exists() {
+        errors.push(format!("{} references missing sound {}", owner, path.display()));
+    }
+}
+
+fn check_door(door: &EntityInstance, levels: &[Level], location: &str, errors: &mut Vec<String>) {
+    let target_level = entity_string(door, DOOR_TARGET_LEVEL_FIELD);
+    let target_spawn = entity_ref(door, DOOR_TARGET_SPAWN_FIELD);
+    if target_level.is_none() && target_spawn.is_none() {
+        errors.push(format!("{} has neither {} nor {}", location, DOOR_TARGET_LEVEL_FIELD, DOOR_TARGET_SPAWN_FIELD));
+    }
+    if let Some(target_level) = target_level {
+        if !levels.iter().any(|level| level.identifier == target_level) {
+            errors.push(format!("{} targets unknown level {}", location, target_level));
+        }
+    }
+    if let Some(target_spawn) = target_spawn {
+        let spawn_exists = levels.iter()
+            .filter(|level| level.iid == target_spawn.level_iid)
+            .flat_map(level_entities)
+            .any(|entity_instance| entity_instance.iid == target_spawn.entity_iid);
+        if !spawn_exists {
+            errors.push(format!("{} targets unknown spawn {}", location, target_spawn.entity_iid));
+        }
+    }
+}
+
+fn check_collider_overlaps(level: &Level, errors: &mut Vec<String>) {
+    let colliders: Vec<(&EntityInstance, Vec2, Vec2)> = level_entities(level)
+        .filter(|entity_instance| COLLIDER_ENTITIES.contains(&entity_instance.identifier.as_str()))
+        .map(|entity_instance| {
+            let collider = Collider::from(entity_instance.clone());
+            let position = spawn_translation(level, entity_instance) + collider.0.offset;
+            (entity_instance, position, position + collider.0.size)
+        })
+        .collect();
+    for (index, (a_instance, a_pos, a_len)) in colliders.iter().enumerate() {
+        for (b_instance, b_pos, b_len) in colliders.iter().skip(index + 1) {
+            let overlapping = a_len.x > b_pos.x && a_pos.x < b_len.x && a_len.y > b_pos.y && a_pos.y < b_len.y;
+            if overlapping {
+                errors.push(format!(
+                    "{} {} overlaps {} {} at spawn in {}",
+                    a_instance.identifier, a_instance.iid, b_instance.identifier, b_instance.iid, level.identifier
+                ));
+            }
+        }
+    }
+
+    // same placement bevy_ecs_ldtk uses: the center of the entity, with y growing upwards
+    fn spawn_translation(level: &Level, entity_instance: &EntityInstance) -> Vec2 {
+        let size = Vec2::new(entity_instance.width as f32, entity_instance.height as f32);
+        let top_left = entity_instance.px.as_vec2() - entity_instance.pivot * size;
+        Vec2::new(top_left.x + size.x / 2., level.px_hei as f32 - top_left.y - size.y / 2.)
+    }
+}
@@ -1,8 +1,19 @@
 use bevy::ecs::component::Component;
 use bevy::ecs::bundle::Bundle;
-use bevy::prelude::SpriteSheetBundle;
-use bevy_ecs_ldtk::LdtkEntity;
+use bevy::math::Vec2;
+use bevy::prelude::{Color, Entity, SpriteSheetBundle, TimerMode};
+use bevy::time::Timer;
+use bevy::utils::default;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+use bevy_ecs_ldtk::prelude::Worldly;
+use bevy_inspector_egui::Inspectable;
 use crate::modules::camera::components::CameraTarget;
+use crate::modules::crafting::components::CraftingQueue;
+use crate::modules::gathering::components::Tools;
+use crate::modules::inventory::components::Inventory;
+use crate::modules::level::consts::{HEALTH_FIELD, STAMINA_FIELD};
+use crate::modules::level::fields::entity_float;
+use crate::modules::living::consts::{ATTACK_COOLDOWN_SECONDS, DEFAULT_HEALTH, DEFAULT_STAMINA, INVULNERABILITY_SECONDS, STAMINA_REGEN_DELAY_SECONDS};
 use crate::modules::physics::components::{Collider, SelfPhysicalBundle};
 use crate::modules::physics::sprite_change::components::{MultipleMovementState, MultipleSided, SpriteZone};
 use crate::modules::simple::components::Name;
@@ -13,11 +24,124 @@ pub struct Living;
 #[derive(Default, Component)]
 pub struct Person;
 
+#[derive(Component, Inspectable)]
+pub struct Health {
+    pub max: f32,
+    pub current: f32,
+}
+
+impl Health {
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.
+    }
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Health { max: DEFAULT_HEALTH, current: DEFAULT_HEALTH }
+    }
+}
+
+impl From<EntityInstance> for Health {
+    fn from(entity_instance: EntityInstance) -> Health {
+        let max = entity_float(&entity_instance, HEALTH_FIELD).unwrap_or(DEFAULT_HEALTH);
+        Health { max, current: max }
+    }
+}
+
+#[derive(Component, Inspectable)]
+pub struct Stamina {
+    pub max: f32,
+    pub current: f32,
+    pub exhausted: bool,
+    #[inspectable(ignore)]
+    pub regen_delay: Timer,
+}
+
+impl Stamina {
+    pub fn can_sprint(&self) -> bool {
+        !self.exhausted && self.current > 0.
+    }
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Stamina {
+            max: DEFAULT_STAMINA,
+            current: DEFAULT_STAMINA,
+            exhausted: false,
+            regen_delay: Timer::from_seconds(STAMINA_REGEN_DELAY_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl From<EntityInstance> for Stamina {
+    fn from(entity_instance: EntityInstance) -> Stamina {
+        let max = entity_float(&entity_instance, STAMINA_FIELD).unwrap_or(DEFAULT_STAMINA);
+        Stamina { max, current: max, ..default() }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct StaminaBar;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DamageType { BLUNT, SHARP, FIRE, COLD }
+
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub damage_type: DamageType,
+    pub knockback: f32,
+}
+
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub source: Option<Entity>,
+    pub damage_type: DamageType,
+    pub identifier: Option<String>,
+    pub position: Vec2,
+}
+
+// inserted on hit, the entity can't be damaged again until it is removed
+#[derive(Component)]
+pub struct Hurt {
+    pub timer: Timer,
+    // the sprite's colour before the flashing, given back once it is over
+    pub color: Option<Color>,
+}
+
+impl Default for Hurt {
+    fn default() -> Self {
+        Hurt { timer: Timer::from_seconds(INVULNERABILITY_SECONDS, TimerMode::Once), color: None }
+    }
+}
+
+// inserted on attack, the next one can't start before it is removed
+#[derive(Component)]
+pub struct Attacking {
+    pub timer: Timer,
+}
+
+impl Default for Attacking {
+    fn default() -> Self {
+        Attacking { timer: Timer::from_seconds(ATTACK_COOLDOWN_SECONDS, TimerMode::Once) }
+    }
+}
+
 #[derive(LdtkEntity, Bundle, Default)]
 pub struct PersonBundle {
     pub person: Person,
     pub living: Living,
     pub name: Name,
+
+    #[from_entity_instance]
+    pub health: Health,
+
+    #[from_entity_instance]
+    pub stamina: Stamina,
+
     pub multiple_sided: MultipleSided,
     pub multiple_movement_state: MultipleMovementState,
 
@@ -43,6 +167,12 @@ pub struct Player;
 pub struct PlayerBundle {
     pub player: Player,
     pub camera_target: CameraTarget,
+    pub tools: Tools,
+    pub inventory: Inventory,
+    pub crafting_queue: CraftingQueue,
+
+    #[worldly]
+    pub worldly: Worldly,
 
     #[ldtk_entity]
     #[bundle]
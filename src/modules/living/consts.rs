@@ -0,0 +1,31 @@
+use bevy::prelude::KeyCode;
+
+pub const DEFAULT_HEALTH: f32 = 5.;
+
+pub const INVULNERABILITY_SECONDS: f32 = 0.8;
+pub const HURT_FLASH_SECONDS: f32 = 0.1;
+pub const HURT_COLOR: (f32, f32, f32) = (1., 0.35, 0.35);
+// each frame of the hurt range is shown this long, once
+pub const HURT_FRAME_SECONDS: f32 = 0.12;
+
+pub const ATTACK_KEY: KeyCode = KeyCode::Space;
+pub const ATTACK_DAMAGE: f32 = 1.;
+pub const ATTACK_RANGE: f32 = 24.;
+// how far off the facing direction something can be and still get hit
+pub const ATTACK_MIN_DOT: f32 = 0.5;
+pub const ATTACK_KNOCKBACK: f32 = 80.;
+pub const ATTACK_COOLDOWN_SECONDS: f32 = 0.4;
+
+pub const DEFAULT_STAMINA: f32 = 4.;
+pub const SPRINT_MULTIPLIER: f32 = 1.8;
+pub const EXHAUSTED_MULTIPLIER: f32 = 0.6;
+pub const STAMINA_DRAIN: f32 = 1.;
+pub const STAMINA_REGEN: f32 = 1.5;
+pub const STAMINA_REGEN_DELAY_SECONDS: f32 = 1.;
+// exhaustion ends once this share of stamina is back
+pub const EXHAUSTION_RECOVERY: f32 = 0.4;
+
+pub const STAMINA_BAR_WIDTH: f32 = 120.;
+pub const STAMINA_BAR_HEIGHT: f32 = 8.;
+pub const STAMINA_BAR_COLOR: (f32, f32, f32) = (0.4, 0.8, 0.3);
+pub const STAMINA_BAR_EXHAUSTED_COLOR: (f32, f32, f32) = (0.8, 0.3, 0.2);
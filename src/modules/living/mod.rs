@@ -1 +1,3 @@
-pub mod components;
\ No newline at end of file
+pub mod components;
+pub mod consts;
+pub mod systems;
\ No newline at end of file
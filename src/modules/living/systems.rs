@@ -0,0 +1,185 @@
+use bevy::input::Input;
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::{BackgroundColor, Color, Commands, DespawnRecursiveExt, Entity, EventReader, EventWriter, GlobalTransform, KeyCode, Query, Res, ResMut, Style, TextureAtlasSprite, Time, Val, With};
+use bevy::utils::default;
+use bevy_ecs_ldtk::EntityInstance;
+use crate::modules::level::components::{LastCheckpoint, LevelTransition};
+use crate::modules::living::components::{Attacking, DamageEvent, DamageType, DeathEvent, Health, Hurt, Living, Player, Stamina, StaminaBar};
+use crate::modules::living::consts::{ATTACK_DAMAGE, ATTACK_KEY, ATTACK_KNOCKBACK, ATTACK_MIN_DOT, ATTACK_RANGE, EXHAUSTION_RECOVERY, HURT_COLOR, HURT_FLASH_SECONDS, HURT_FRAME_SECONDS, STAMINA_BAR_COLOR, STAMINA_BAR_EXHAUSTED_COLOR, STAMINA_DRAIN, STAMINA_REGEN};
+use crate::modules::physics::components::{Physical, SelfPhysical};
+use crate::modules::physics::sprite_change::components::{MultipleMovementState, MultipleSided, Side};
+use crate::modules::physics::sprite_change::consts::{MOB_BOTTOM_HURT_END, MOB_BOTTOM_HURT_START, MOB_SIDE_HURT_END, MOB_SIDE_HURT_START, MOB_TOP_HURT_END, MOB_TOP_HURT_START};
+use crate::modules::world_state::components::WorldState;
+
+#[allow(clippy::type_complexity)]
+pub fn apply_damage(
+    mut commands: Commands, mut damage_events: EventReader<DamageEvent>, mut death_events: EventWriter<DeathEvent>,
+    mut targets: Query<(&mut Health, &GlobalTransform, Option<&mut Physical>, Option<&Hurt>, Option<&EntityInstance>)>,
+    transforms: Query<&GlobalTransform>
+) {
+    for damage_event in damage_events.iter() {
+        let source_position = damage_event.source
+            .and_then(|source| transforms.get(source).ok())
+            .map(|transform| transform.translation());
+        let (mut health, transform, physical, hurt, entity_instance) = match targets.get_mut(damage_event.target) {
+            Ok(target) => target,
+            _ => continue
+        };
+        if hurt.is_some() || health.is_dead() {
+            continue;
+        }
+        health.current = (health.current - damage_event.amount).clamp(0., health.max);
+        commands.entity(damage_event.target).insert(Hurt {..default()});
+
+        if let (Some(mut physical), Some(source_position)) = (physical, source_position) {
+            let direction = (transform.translation() - source_position).truncate().normalize_or_zero();
+            if damage_event.knockback > 0. && direction != Vec2::ZERO {
+                physical.direction = direction.extend(0.);
+                physical.acceleration = damage_event.knockback;
+            }
+        }
+
+        if health.is_dead() {
+            death_events.send(DeathEvent {
+                entity: damage_event.target,
+                source: damage_event.source,
+                damage_type: damage_event.damage_type,
+                identifier: entity_instance.map(|entity_instance| entity_instance.identifier.clone()),
+                position: transform.translation().truncate(),
+            });
+        }
+    }
+}
+
+// flashes and plays the hurt frames of the side the entity faces, then gives the sprite its colour back
+#[allow(clippy::type_complexity)]
+pub fn update_hurt(
+    mut commands: Commands, time: Res<Time>,
+    mut hurts: Query<(Entity, &mut Hurt, Option<&mut TextureAtlasSprite>, Option<(&MultipleSided, &MultipleMovementState)>)>
+) {
+    for (entity, mut hurt, sprite, sided) in hurts.iter_mut() {
+        hurt.timer.tick(time.delta());
+        if let Some(mut sprite) = sprite {
+            let color = *hurt.color.get_or_insert(sprite.color);
+            let flashing = !hurt.timer.finished() && ((hurt.timer.elapsed_secs() / HURT_FLASH_SECONDS) as u32).is_multiple_of(2);
+            sprite.color = if flashing {
+                Color::rgb(HURT_COLOR.0, HURT_COLOR.1, HURT_COLOR.2)
+            } else {
+                color
+            };
+            if let Some((multiple_sided, _)) = sided {
+                let (start, end) = match multiple_sided.side {
+                    Side::BOTTOM => (MOB_BOTTOM_HURT_START, MOB_BOTTOM_HURT_END),
+                    Side::TOP => (MOB_TOP_HURT_START, MOB_TOP_HURT_END),
+                    Side::LEFT | Side::RIGHT => (MOB_SIDE_HURT_START, MOB_SIDE_HURT_END),
+                };
+                let frame = start + (hurt.timer.elapsed_secs() / HURT_FRAME_SECONDS) as usize;
+                if frame <= end {
+                    sprite.index = frame;
+                    sprite.flip_x = multiple_sided.side == Side::RIGHT;
+                }
+            }
+        }
+        if hurt.timer.finished() {
+            commands.entity(entity).remove::<Hurt>();
+        }
+    }
+}
+
+// hits whatever lives in front of the player
+#[allow(clippy::type_complexity)]
+pub fn attack(
+    mut commands: Commands, keyboard_input: Res<Input<KeyCode>>, transition: Res<LevelTransition>,
+    players: Query<(Entity, &GlobalTransform, &MultipleSided, Option<&Attacking>), With<Player>>,
+    targets: Query<(Entity, &GlobalTransform), (With<Living>, With<Health>)>,
+    mut damage_events: EventWriter<DamageEvent>
+) {
+    if !keyboard_input.just_pressed(ATTACK_KEY) || transition.is_active() {
+        return;
+    }
+    let (player, transform, multiple_sided) = match players.get_single() {
+        Ok((player, transform, multiple_sided, None)) => (player, transform, multiple_sided),
+        _ => return
+    };
+    commands.entity(player).insert(Attacking {..default()});
+    let position = transform.translation().truncate();
+    let facing = multiple_sided.side.direction();
+    for (target, target_transform) in targets.iter() {
+        let offset = target_transform.translation().truncate() - position;
+        if target == player || offset.length() > ATTACK_RANGE || offset.normalize_or_zero().dot(facing) < ATTACK_MIN_DOT {
+            continue;
+        }
+        damage_events.send(DamageEvent {
+            target,
+            source: Some(player),
+            amount: ATTACK_DAMAGE,
+            damage_type: DamageType::BLUNT,
+            knockback: ATTACK_KNOCKBACK,
+        });
+    }
+}
+
+pub fn update_attacking(mut commands: Commands, time: Res<Time>, mut attackers: Query<(Entity, &mut Attacking)>) {
+    for (entity, mut attacking) in attackers.iter_mut() {
+        if attacking.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Attacking>();
+        }
+    }
+}
+
+pub fn handle_deaths(
+    mut commands: Commands, mut death_events: EventReader<DeathEvent>, mut world_state: ResMut<WorldState>,
+    mut transition: ResMut<LevelTransition>, last_checkpoint: Res<LastCheckpoint>,
+    mut players: Query<&mut Health, With<Player>>, entity_instances: Query<&EntityInstance>
+) {
+    for death_event in death_events.iter() {
+        if let Ok(mut health) = players.get_mut(death_event.entity) {
+            health.current = health.max;
+            if let Some(position) = last_checkpoint.position {
+                transition.start_respawn(last_checkpoint.level.clone(), position);
+            }
+            continue;
+        }
+        if let Ok(entity_instance) = entity_instances.get(death_event.entity) {
+            world_state.destroy(&entity_instance.iid);
+        }
+        commands.entity(death_event.entity).despawn_recursive();
+    }
+}
+
+
+pub fn update_stamina(time: Res<Time>, mut entities: Query<(&mut Stamina, &SelfPhysical)>) {
+    for (mut stamina, self_physical) in entities.iter_mut() {
+        let sprinting = self_physical.multiplier > 1. && self_physical.direction != Vec3::ZERO;
+        if sprinting {
+            stamina.current = (stamina.current - STAMINA_DRAIN * time.delta_seconds()).max(0.);
+            stamina.regen_delay.reset();
+            if stamina.current == 0. {
+                stamina.exhausted = true;
+            }
+        } else if stamina.current < stamina.max && stamina.regen_delay.tick(time.delta()).finished() {
+            stamina.current = (stamina.current + STAMINA_REGEN * time.delta_seconds()).min(stamina.max);
+        }
+        if stamina.exhausted && stamina.current >= stamina.max * EXHAUSTION_RECOVERY {
+            stamina.exhausted = false;
+        }
+    }
+}
+
+pub fn update_stamina_bar(
+    players: Query<&Stamina, With<Player>>, mut bars: Query<(&mut Style, &mut BackgroundColor), With<StaminaBar>>
+) {
+    let stamina = match players.get_single() {
+        Ok(stamina) => stamina,
+        _ => return
+    };
+    let (red, green, blue) = if stamina.exhausted {
+        STAMINA_BAR_EXHAUSTED_COLOR
+    } else {
+        STAMINA_BAR_COLOR
+    };
+    for (mut style, mut color) in bars.iter_mut() {
+        style.size.width = Val::Percent(stamina.current / stamina.max * 100.);
+        color.0 = Color::rgb(red, green, blue);
+    }
+}
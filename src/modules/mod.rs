@@ -6,4 +6,24 @@ pub mod prop;
 pub mod physics;
 pub mod living;
 pub mod sound;
-pub mod rng;
\ No newline at end of file
+pub mod rng;
+pub mod level;
+pub mod music;
+pub mod storage;
+pub mod world_state;
+pub mod save;
+pub mod hot_reload;
+pub mod worldgen;
+pub mod npc;
+pub mod ai;
+pub mod navigation;
+pub mod animal;
+pub mod interaction;
+pub mod gathering;
+pub mod inventory;
+pub mod crafting;
+pub mod tech;
+pub mod building;
+pub mod clock;
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,79 @@
+use bevy::asset::Handle;
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::math::Vec2;
+use bevy::utils::HashMap;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+use bevy_kira_audio::AudioInstance;
+use crate::modules::level::fields::{entity_string, entity_strings};
+use crate::modules::music::consts::{AMBIENCE_FIELD, MUSIC_FIELD, NIGHT_AMBIENCE_FIELD};
+
+#[derive(Clone, Default)]
+pub struct LevelMusic {
+    pub track: Option<String>,
+    pub ambience: Vec<String>,
+    pub night_ambience: Vec<String>,
+}
+
+impl LevelMusic {
+    pub fn ambience(&self, night: bool) -> &[String] {
+        if night && !self.night_ambience.is_empty() { &self.night_ambience } else { &self.ambience }
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct MusicController {
+    pub level: Option<LevelMusic>,
+    pub track: Option<String>,
+    pub instance: Option<Handle<AudioInstance>>,
+    pub ambience: HashMap<String, Handle<AudioInstance>>,
+}
+
+#[derive(Resource)]
+pub struct MusicDucking {
+    pub active: bool,
+    pub volume: f64,
+}
+
+impl Default for MusicDucking {
+    fn default() -> Self {
+        MusicDucking { active: false, volume: 1. }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct MusicZone {
+    pub track: Option<String>,
+    pub ambience: Vec<String>,
+    pub night_ambience: Vec<String>,
+    pub size: Vec2,
+}
+
+impl MusicZone {
+    pub fn ambience(&self, night: bool) -> &[String] {
+        if night && !self.night_ambience.is_empty() { &self.night_ambience } else { &self.ambience }
+    }
+
+    pub fn contains(&self, center: Vec2, position: Vec2) -> bool {
+        let distance = (position - center).abs();
+        distance.x <= self.size.x / 2. && distance.y <= self.size.y / 2.
+    }
+}
+
+impl From<EntityInstance> for MusicZone {
+    fn from(entity_instance: EntityInstance) -> MusicZone {
+        MusicZone {
+            track: entity_string(&entity_instance, MUSIC_FIELD),
+            ambience: entity_strings(&entity_instance, AMBIENCE_FIELD),
+            night_ambience: entity_strings(&entity_instance, NIGHT_AMBIENCE_FIELD),
+            size: Vec2::new(entity_instance.width as f32, entity_instance.height as f32),
+        }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct MusicZoneBundle {
+    #[from_entity_instance]
+    pub music_zone: MusicZone,
+}
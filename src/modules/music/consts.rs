@@ -0,0 +1,15 @@
+use crate::modules::sound::components::SoundChannel;
+
+pub const MUSIC_PATH: &str = "sounds/music";
+pub const AMBIENCE_PATH: &str = "sounds/ambience";
+
+pub const MUSIC_FIELD: &str = "Music";
+pub const AMBIENCE_FIELD: &str = "Ambience";
+// played instead of the ambience between dusk and dawn
+pub const NIGHT_AMBIENCE_FIELD: &str = "Night_Ambience";
+
+pub const MUSIC_CROSSFADE_SECONDS: f32 = 2.;
+pub const MUSIC_DUCKED_VOLUME: f64 = 0.3;
+pub const MUSIC_DUCKING_SECONDS: f64 = 0.5;
+// music makes room while sounds on these channels play
+pub const DUCKING_CHANNELS: &[SoundChannel] = &[SoundChannel::UI];
@@ -0,0 +1,107 @@
+use std::time::Duration;
+use bevy::asset::{Assets, AssetServer, Handle};
+use bevy::prelude::{GlobalTransform, Query, Res, ResMut, Time, With};
+use bevy_ecs_ldtk::LevelSelection;
+use bevy_ecs_ldtk::prelude::LdtkAsset;
+use bevy_kira_audio::{AudioChannel, AudioControl, AudioInstance, AudioTween};
+use crate::modules::clock::components::WorldClock;
+use crate::modules::level::fields::{string_field, strings_field};
+use crate::modules::living::components::Player;
+use crate::modules::music::components::{LevelMusic, MusicController, MusicDucking, MusicZone};
+use crate::modules::music::consts::{AMBIENCE_FIELD, AMBIENCE_PATH, DUCKING_CHANNELS, MUSIC_CROSSFADE_SECONDS, MUSIC_DUCKED_VOLUME, MUSIC_DUCKING_SECONDS, MUSIC_FIELD, MUSIC_PATH, NIGHT_AMBIENCE_FIELD};
+use crate::modules::sound::components::{AmbienceChannel, MusicChannel, PlayingSounds};
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_music(
+    music: Res<AudioChannel<MusicChannel>>, ambience: Res<AudioChannel<AmbienceChannel>>,
+    asset_server: Res<AssetServer>, mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut controller: ResMut<MusicController>, level_selection: Res<LevelSelection>,
+    ldtk_worlds: Query<&Handle<LdtkAsset>>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    players: Query<&GlobalTransform, With<Player>>, zones: Query<(&MusicZone, &GlobalTransform)>,
+    clock: Res<WorldClock>
+) {
+    if controller.level.is_none() || level_selection.is_changed() {
+        let level = ldtk_worlds.iter()
+            .filter_map(|ldtk_handle| ldtk_assets.get(ldtk_handle))
+            .find_map(|ldtk_asset| ldtk_asset.get_level(&level_selection));
+        controller.level = level.map(|level| LevelMusic {
+            track: string_field(&level.identifier, &level.field_instances, MUSIC_FIELD),
+            ambience: strings_field(&level.identifier, &level.field_instances, AMBIENCE_FIELD),
+            night_ambience: strings_field(&level.identifier, &level.field_instances, NIGHT_AMBIENCE_FIELD),
+        });
+    }
+    let night = clock.is_night();
+    let (mut track, mut ambience_names) = match &controller.level {
+        Some(level_music) => (level_music.track.clone(), level_music.ambience(night).to_vec()),
+        None => return
+    };
+
+    if let Ok(player) = players.get_single() {
+        let position = player.translation().truncate();
+        let zone = zones.iter()
+            .find(|(zone, transform)| zone.contains(transform.translation().truncate(), position));
+        if let Some((zone, _)) = zone {
+            if zone.track.is_some() {
+                track = zone.track.clone();
+            }
+            if !zone.ambience(night).is_empty() {
+                ambience_names = zone.ambience(night).to_vec();
+            }
+        }
+    }
+
+    if track != controller.track {
+        if let Some(instance) = controller.instance.take() {
+            if let Some(instance) = audio_instances.get_mut(&instance) {
+                instance.stop(crossfade());
+            }
+        }
+        controller.instance = track.as_ref().map(|track| {
+            music.play(asset_server.load(format!("{}/{}.wav", MUSIC_PATH, track)))
+                .looped()
+                .fade_in(crossfade())
+                .handle()
+        });
+        controller.track = track;
+    }
+
+    controller.ambience.retain(|name, instance| {
+        if ambience_names.contains(name) {
+            return true;
+        }
+        if let Some(instance) = audio_instances.get_mut(instance) {
+            instance.stop(crossfade());
+        }
+        false
+    });
+    for name in ambience_names {
+        if !controller.ambience.contains_key(&name) {
+            let instance = ambience.play(asset_server.load(format!("{}/{}.wav", AMBIENCE_PATH, name)))
+                .looped()
+                .fade_in(crossfade())
+                .handle();
+            controller.ambience.insert(name, instance);
+        }
+    }
+
+    fn crossfade() -> AudioTween {
+        AudioTween::linear(Duration::from_secs_f32(MUSIC_CROSSFADE_SECONDS))
+    }
+}
+
+pub fn duck_music(mut ducking: ResMut<MusicDucking>, playing_sounds: Res<PlayingSounds>, time: Res<Time>) {
+    let active = playing_sounds.sounds.iter().any(|sound| DUCKING_CHANNELS.contains(&sound.channel));
+    if ducking.active != active {
+        ducking.active = active;
+    }
+    let target = if ducking.active { MUSIC_DUCKED_VOLUME } else { 1. };
+    if ducking.volume == target {
+        return;
+    }
+    let step = time.delta_seconds() as f64 / MUSIC_DUCKING_SECONDS;
+    ducking.volume = if ducking.volume < target {
+        (ducking.volume + step).min(target)
+    } else {
+        (ducking.volume - step).max(target)
+    };
+}
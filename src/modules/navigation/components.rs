@@ -0,0 +1,125 @@
+use std::collections::{HashMap, VecDeque};
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::math::{IVec2, Vec2};
+use bevy::prelude::Entity;
+use crate::modules::navigation::consts::{NAV_CELL_SIZE, PATH_CACHE_CAPACITY};
+
+// sparse, so streamed levels anywhere in the world fit without resizing
+#[derive(Default, Resource)]
+pub struct NavGrid {
+    pub blocked: HashMap<IVec2, u32>,
+    pub footprints: HashMap<Entity, Vec<IVec2>>,
+    // bumped on every change so cached paths can be dropped
+    pub version: u64,
+}
+
+impl NavGrid {
+    pub fn cell(position: Vec2) -> IVec2 {
+        (position / NAV_CELL_SIZE).floor().as_ivec2()
+    }
+
+    pub fn center(cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + Vec2::splat(0.5)) * NAV_CELL_SIZE
+    }
+
+    pub fn cells_overlapping(min: Vec2, max: Vec2) -> Vec<IVec2> {
+        let first = NavGrid::cell(min);
+        // a box ending exactly on a cell border doesn't reach into the next cell
+        let last = NavGrid::cell(max - Vec2::splat(0.01));
+        (first.y..=last.y)
+            .flat_map(|y| (first.x..=last.x).map(move |x| IVec2::new(x, y)))
+            .collect()
+    }
+
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        self.blocked.contains_key(&cell)
+    }
+
+    pub fn set_footprint(&mut self, entity: Entity, cells: Vec<IVec2>) {
+        if self.footprints.get(&entity) == Some(&cells) {
+            return;
+        }
+        self.remove_footprint(entity);
+        for cell in cells.iter() {
+            *self.blocked.entry(*cell).or_insert(0) += 1;
+        }
+        self.footprints.insert(entity, cells);
+        self.version += 1;
+    }
+
+    pub fn remove_footprint(&mut self, entity: Entity) {
+        let cells = match self.footprints.remove(&entity) {
+            Some(cells) => cells,
+            None => return
+        };
+        for cell in cells {
+            if let Some(count) = self.blocked.get_mut(&cell) {
+                *count -= 1;
+                if *count == 0 {
+                    self.blocked.remove(&cell);
+                }
+            }
+        }
+        self.version += 1;
+    }
+}
+
+pub struct PathRequest {
+    pub entity: Entity,
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+#[derive(Default, Resource)]
+pub struct PathRequests {
+    pub queue: VecDeque<PathRequest>,
+}
+
+impl PathRequests {
+    pub fn request(&mut self, entity: Entity, from: Vec2, to: Vec2) {
+        self.queue.retain(|request| request.entity != entity);
+        self.queue.push_back(PathRequest { entity, from, to });
+    }
+
+    pub fn is_pending(&self, entity: Entity) -> bool {
+        self.queue.iter().any(|request| request.entity == entity)
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct PathCache {
+    pub version: u64,
+    pub paths: HashMap<(IVec2, IVec2), Option<Vec<Vec2>>>,
+    pub order: VecDeque<(IVec2, IVec2)>,
+}
+
+impl PathCache {
+    pub fn get(&mut self, version: u64, key: (IVec2, IVec2)) -> Option<&Option<Vec<Vec2>>> {
+        if self.version != version {
+            self.version = version;
+            self.paths.clear();
+            self.order.clear();
+        }
+        self.paths.get(&key)
+    }
+
+    pub fn insert(&mut self, key: (IVec2, IVec2), path: Option<Vec<Vec2>>) {
+        if self.order.len() >= PATH_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.paths.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        self.paths.insert(key, path);
+    }
+}
+
+// result of a path request, inserted on the requesting entity
+#[derive(Component)]
+pub struct NavPath {
+    pub target: Vec2,
+    pub version: u64,
+    pub waypoints: VecDeque<Vec2>,
+    pub found: bool,
+}
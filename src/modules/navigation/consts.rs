@@ -0,0 +1,10 @@
+use crate::modules::level::consts::LEVEL_GRID_SIZE;
+
+pub const NAV_CELL_SIZE: f32 = LEVEL_GRID_SIZE / 2.;
+pub const COLLISION_INT_GRID_VALUE: i32 = 1;
+
+pub const PATH_REQUESTS_PER_FRAME: usize = 8;
+pub const PATH_CACHE_CAPACITY: usize = 128;
+pub const MAX_SEARCH_NODES: usize = 4096;
+
+pub const WAYPOINT_REACHED_DISTANCE: f32 = 3.;
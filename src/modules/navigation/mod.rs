@@ -0,0 +1,4 @@
+pub mod components;
+pub mod consts;
+pub mod pathfinding;
+pub mod systems;
\ No newline at end of file
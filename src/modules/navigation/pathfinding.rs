@@ -0,0 +1,214 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use bevy::math::{IVec2, Vec2};
+use crate::modules::navigation::components::NavGrid;
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0), IVec2::new(-1, 0), IVec2::new(0, 1), IVec2::new(0, -1),
+    IVec2::new(1, 1), IVec2::new(1, -1), IVec2::new(-1, 1), IVec2::new(-1, -1),
+];
+
+fn octile_distance(from: IVec2, to: IVec2) -> u32 {
+    let delta = (to - from).abs();
+    let (long, short) = (delta.x.max(delta.y) as u32, delta.x.min(delta.y) as u32);
+    STRAIGHT_COST * (long - short) + DIAGONAL_COST * short
+}
+
+// A* over 8 neighbours without cutting corners, returns cell centers from start to goal
+pub fn find_cells(grid: &NavGrid, start: IVec2, goal: IVec2, max_nodes: usize) -> Option<Vec<IVec2>> {
+    if grid.is_blocked(goal) {
+        return None;
+    }
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+    let mut costs: HashMap<IVec2, u32> = HashMap::new();
+    open.push(Reverse((octile_distance(start, goal), start.x, start.y)));
+    costs.insert(start, 0);
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, x, y))) = open.pop() {
+        let cell = IVec2::new(x, y);
+        if cell == goal {
+            let mut cells = vec![goal];
+            let mut current = goal;
+            while let Some(previous) = came_from.get(&current) {
+                current = *previous;
+                cells.push(current);
+            }
+            cells.reverse();
+            return Some(cells);
+        }
+        expanded += 1;
+        if expanded > max_nodes {
+            return None;
+        }
+        let cost = costs[&cell];
+        for offset in NEIGHBOURS {
+            let neighbour = cell + offset;
+            if grid.is_blocked(neighbour) {
+                continue;
+            }
+            let diagonal = offset.x != 0 && offset.y != 0;
+            if diagonal && (grid.is_blocked(IVec2::new(cell.x + offset.x, cell.y)) || grid.is_blocked(IVec2::new(cell.x, cell.y + offset.y))) {
+                continue;
+            }
+            let neighbour_cost = cost + if diagonal { DIAGONAL_COST } else { STRAIGHT_COST };
+            if costs.get(&neighbour).is_some_and(|known| *known <= neighbour_cost) {
+                continue;
+            }
+            costs.insert(neighbour, neighbour_cost);
+            came_from.insert(neighbour, cell);
+            open.push(Reverse((neighbour_cost + octile_distance(neighbour, goal), neighbour.x, neighbour.y)));
+        }
+    }
+    None
+}
+
+// walks every cell the segment between two cell centers touches
+pub fn line_clear(grid: &NavGrid, from: IVec2, to: IVec2) -> bool {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut cell = from;
+    let mut error = delta.x - delta.y;
+    while cell != to {
+        let doubled = error * 2;
+        let (move_x, move_y) = (doubled > -delta.y, doubled < delta.x);
+        if move_x && move_y {
+            // passing a corner exactly, both side cells must be free
+            if grid.is_blocked(IVec2::new(cell.x + step.x, cell.y)) || grid.is_blocked(IVec2::new(cell.x, cell.y + step.y)) {
+                return false;
+            }
+        }
+        if move_x {
+            error -= delta.y;
+            cell.x += step.x;
+        }
+        if move_y {
+            error += delta.x;
+            cell.y += step.y;
+        }
+        if grid.is_blocked(cell) {
+            return false;
+        }
+    }
+    true
+}
+
+// string pulling: keeps only the cells where the straight line would hit something
+pub fn smooth(grid: &NavGrid, cells: &[IVec2]) -> Vec<IVec2> {
+    let mut smoothed = Vec::new();
+    let mut anchor = 0;
+    while anchor < cells.len() - 1 {
+        let mut next = cells.len() - 1;
+        while next > anchor + 1 && !line_clear(grid, cells[anchor], cells[next]) {
+            next -= 1;
+        }
+        smoothed.push(cells[next]);
+        anchor = next;
+    }
+    smoothed
+}
+
+// whether the segments from `from` through every waypoint still only touch free cells
+pub fn waypoints_clear<'a>(grid: &NavGrid, from: Vec2, waypoints: impl IntoIterator<Item = &'a Vec2>) -> bool {
+    let mut cell = NavGrid::cell(from);
+    for waypoint in waypoints {
+        let next = NavGrid::cell(*waypoint);
+        if !line_clear(grid, cell, next) {
+            return false;
+        }
+        cell = next;
+    }
+    true
+}
+
+pub fn find_path(grid: &NavGrid, from: Vec2, to: Vec2, max_nodes: usize) -> Option<Vec<Vec2>> {
+    let cells = find_cells(grid, NavGrid::cell(from), NavGrid::cell(to), max_nodes)?;
+    Some(smooth(grid, &cells).into_iter().map(NavGrid::center).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Entity;
+    use super::*;
+
+    fn grid(blocked: &[(i32, i32)]) -> NavGrid {
+        let mut grid = NavGrid {..Default::default()};
+        let cells = blocked.iter().map(|(x, y)| IVec2::new(*x, *y)).collect();
+        grid.set_footprint(Entity::from_raw(0), cells);
+        grid
+    }
+
+    fn wall() -> NavGrid {
+        grid(&[(2, -2), (2, -1), (2, 0), (2, 1), (2, 2)])
+    }
+
+    fn assert_connected(grid: &NavGrid, cells: &[IVec2]) {
+        for pair in cells.windows(2) {
+            let step = pair[1] - pair[0];
+            assert!(step.x.abs() <= 1 && step.y.abs() <= 1 && step != IVec2::ZERO, "{:?} jumps", pair);
+            assert!(!grid.is_blocked(pair[1]), "{:?} is blocked", pair[1]);
+        }
+    }
+
+    #[test]
+    fn finds_a_path_around_an_obstacle() {
+        let grid = wall();
+        let cells = find_cells(&grid, IVec2::ZERO, IVec2::new(4, 0), 1000).unwrap();
+        assert_eq!(cells.first(), Some(&IVec2::ZERO));
+        assert_eq!(cells.last(), Some(&IVec2::new(4, 0)));
+        assert_connected(&grid, &cells);
+        assert!(cells.iter().any(|cell| cell.y.abs() > 2));
+    }
+
+    #[test]
+    fn does_not_cut_corners() {
+        let grid = grid(&[(1, 0)]);
+        let cells = find_cells(&grid, IVec2::ZERO, IVec2::new(1, 1), 1000).unwrap();
+        assert_eq!(cells, vec![IVec2::ZERO, IVec2::new(0, 1), IVec2::new(1, 1)]);
+        assert!(!line_clear(&grid, IVec2::ZERO, IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn unreachable_goals_have_no_path() {
+        let ring: Vec<(i32, i32)> = (-1..=1).flat_map(|x| (-1..=1).map(move |y| (x + 5, y)))
+            .filter(|cell| *cell != (5, 0))
+            .collect();
+        let grid = grid(&ring);
+        assert_eq!(find_cells(&grid, IVec2::ZERO, IVec2::new(5, 0), 1000), None);
+        assert_eq!(find_cells(&grid, IVec2::ZERO, IVec2::new(4, 0), 1000), None);
+    }
+
+    #[test]
+    fn smoothing_never_crosses_a_blocked_cell() {
+        let grid = grid(&[(2, -2), (2, -1), (2, 0), (2, 1), (2, 2), (5, 1), (5, 2), (5, 3), (5, 4), (6, 4)]);
+        let cells = find_cells(&grid, IVec2::ZERO, IVec2::new(8, 3), 4000).unwrap();
+        let smoothed = smooth(&grid, &cells);
+        assert!(smoothed.len() < cells.len() - 1);
+        assert_eq!(smoothed.last(), Some(&IVec2::new(8, 3)));
+        let mut from = NavGrid::center(IVec2::ZERO);
+        for cell in smoothed.iter() {
+            let to = NavGrid::center(*cell);
+            for step in 0..=100 {
+                let point = from.lerp(to, step as f32 / 100.);
+                assert!(!grid.is_blocked(NavGrid::cell(point)), "{:?} to {:?} crosses {:?}", from, to, point);
+            }
+            from = to;
+        }
+    }
+
+    #[test]
+    fn waypoints_are_only_unclear_once_blocked() {
+        let mut grid = grid(&[]);
+        let waypoints = [NavGrid::center(IVec2::new(4, 0)), NavGrid::center(IVec2::new(4, 4))];
+        let start = NavGrid::center(IVec2::ZERO);
+        assert!(waypoints_clear(&grid, start, waypoints.iter()));
+        grid.set_footprint(Entity::from_raw(1), vec![IVec2::new(0, 4)]);
+        assert!(waypoints_clear(&grid, start, waypoints.iter()));
+        grid.set_footprint(Entity::from_raw(1), vec![IVec2::new(4, 2)]);
+        assert!(!waypoints_clear(&grid, start, waypoints.iter()));
+    }
+}
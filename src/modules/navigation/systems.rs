@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+use bevy::math::Vec2;
+use bevy::prelude::{Changed, Commands, Entity, GlobalTransform, Query, RemovedComponents, Res, ResMut, Without};
+use bevy_ecs_ldtk::prelude::IntGridCell;
+use crate::modules::level::consts::LEVEL_GRID_SIZE;
+use crate::modules::navigation::components::{NavGrid, NavPath, PathCache, PathRequests};
+use crate::modules::navigation::consts::{COLLISION_INT_GRID_VALUE, MAX_SEARCH_NODES, NAV_CELL_SIZE, PATH_REQUESTS_PER_FRAME, WAYPOINT_REACHED_DISTANCE};
+use crate::modules::navigation::pathfinding::{find_path, waypoints_clear};
+use crate::modules::physics::components::{Collider, SelfPhysical};
+
+// only static or pushed colliders block, moving persons steer around each other through physics
+#[allow(clippy::type_complexity)]
+pub fn update_nav_grid(
+    mut grid: ResMut<NavGrid>, removed_colliders: RemovedComponents<Collider>, removed_cells: RemovedComponents<IntGridCell>,
+    colliders: Query<(Entity, &GlobalTransform, &Collider), (Without<SelfPhysical>, Changed<GlobalTransform>)>,
+    int_grid_cells: Query<(Entity, &IntGridCell, &GlobalTransform), Changed<GlobalTransform>>
+) {
+    for entity in removed_colliders.iter().chain(removed_cells.iter()) {
+        grid.remove_footprint(entity);
+    }
+    for (entity, transform, collider) in colliders.iter() {
+        let min = transform.translation().truncate() + collider.0.offset;
+        grid.set_footprint(entity, NavGrid::cells_overlapping(min, min + collider.0.size));
+    }
+    for (entity, int_grid_cell, transform) in int_grid_cells.iter() {
+        if int_grid_cell.value != COLLISION_INT_GRID_VALUE {
+            continue;
+        }
+        let center = transform.translation().truncate();
+        let half_size = Vec2::splat(LEVEL_GRID_SIZE / 2.);
+        grid.set_footprint(entity, NavGrid::cells_overlapping(center - half_size, center + half_size));
+    }
+}
+
+pub fn process_path_requests(
+    mut commands: Commands, grid: Res<NavGrid>, mut cache: ResMut<PathCache>, mut requests: ResMut<PathRequests>
+) {
+    // cached answers are free, only searches count against the budget
+    let mut searches = 0;
+    while searches < PATH_REQUESTS_PER_FRAME {
+        let request = match requests.queue.pop_front() {
+            Some(request) => request,
+            None => break
+        };
+        let key = (NavGrid::cell(request.from), NavGrid::cell(request.to));
+        let path = match cache.get(grid.version, key) {
+            Some(path) => path.clone(),
+            None => {
+                searches += 1;
+                let path = find_path(&grid, request.from, request.to, MAX_SEARCH_NODES);
+                cache.insert(key, path.clone());
+                path
+            }
+        };
+        let found = path.is_some();
+        let mut waypoints: VecDeque<Vec2> = path.unwrap_or_default().into();
+        if found {
+            waypoints.pop_back();
+            waypoints.push_back(request.to);
+        }
+        if let Some(mut entity_commands) = commands.get_entity(request.entity) {
+            entity_commands.insert(NavPath { target: request.to, version: grid.version, waypoints, found });
+        }
+    }
+}
+
+// direction along the entity's path to `destination`, requesting one when needed; None if unreachable
+pub fn steer(
+    entity: Entity, position: Vec2, destination: Vec2, nav_path: Option<&mut NavPath>, grid: &NavGrid,
+    requests: &mut PathRequests
+) -> Option<Vec2> {
+    match nav_path {
+        Some(nav_path) if nav_path.target.distance(destination) < NAV_CELL_SIZE / 2. && still_walkable(nav_path, position, grid) => {
+            nav_path.version = grid.version;
+            if !nav_path.found {
+                return None;
+            }
+            while nav_path.waypoints.front().is_some_and(|waypoint| waypoint.distance(position) <= WAYPOINT_REACHED_DISTANCE) {
+                nav_path.waypoints.pop_front();
+            }
+            Some(nav_path.waypoints.front().map_or(Vec2::ZERO, |waypoint| (*waypoint - position).normalize_or_zero()))
+        }
+        _ => {
+            if !requests.is_pending(entity) {
+                requests.request(entity, position, destination);
+            }
+            Some(Vec2::ZERO)
+        }
+    }
+}
+
+// a grid change only sends the path back to the search if it blocked what is left of it,
+// a missing path is searched again since the change may have opened one
+fn still_walkable(nav_path: &NavPath, position: Vec2, grid: &NavGrid) -> bool {
+    nav_path.version == grid.version || (nav_path.found && waypoints_clear(grid, position, nav_path.waypoints.iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, Transform};
+    use super::*;
+
+    #[test]
+    fn collision_int_grid_cells_block_the_grid() {
+        let mut app = App::new();
+        app.insert_resource(NavGrid {..Default::default()}).add_system(update_nav_grid);
+        let center = Vec2::splat(LEVEL_GRID_SIZE / 2.);
+        let water = app.world.spawn((
+            IntGridCell { value: COLLISION_INT_GRID_VALUE }, GlobalTransform::from(Transform::from_translation(center.extend(0.)))
+        )).id();
+        app.world.spawn((
+            IntGridCell { value: COLLISION_INT_GRID_VALUE + 1 }, GlobalTransform::from(Transform::from_xyz(center.x + LEVEL_GRID_SIZE, center.y, 0.))
+        ));
+        app.update();
+
+        let grid = app.world.resource::<NavGrid>();
+        let covered = NavGrid::cells_overlapping(Vec2::ZERO, Vec2::splat(LEVEL_GRID_SIZE));
+        assert_eq!(covered.len(), 4);
+        assert!(covered.iter().all(|cell| grid.is_blocked(*cell)));
+        assert_eq!(grid.blocked.len(), 4);
+        assert!(!grid.is_blocked(NavGrid::cell(Vec2::new(LEVEL_GRID_SIZE * 1.5, center.y))));
+        assert!(find_path(grid, Vec2::new(-4., 4.), Vec2::new(LEVEL_GRID_SIZE + 4., 4.), MAX_SEARCH_NODES)
+            .is_some_and(|path| path.iter().all(|waypoint| !grid.is_blocked(NavGrid::cell(*waypoint)))));
+
+        app.world.despawn(water);
+        app.update();
+        assert!(app.world.resource::<NavGrid>().blocked.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::ecs::bundle::Bundle;
+use bevy::ecs::component::Component;
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::prelude::TimerMode;
+use bevy::time::Timer;
+use bevy::utils::default;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+use crate::modules::interaction::components::Interactable;
+use crate::modules::level::consts::{LEVEL_GRID_SIZE, PLAYER_REACTION_FIELD, REACTION_DISTANCE_FIELD, SCHEDULE_FIELD, SCHEDULE_HOURS_FIELD, WANDER_RADIUS_FIELD};
+use crate::modules::level::fields::{entity_float, entity_floats, entity_points, entity_string};
+use crate::modules::living::components::PersonBundle;
+use crate::modules::npc::consts::{DEFAULT_REACTION_DISTANCE, DEFAULT_WANDER_RADIUS, IDLE_MIN_SECONDS};
+
+#[derive(Default, Component)]
+pub struct Villager;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PlayerReaction { #[default] NONE, FLEE, APPROACH }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BehaviourState { #[default] IDLE, WANDER, SCHEDULE, REST, FLEE, APPROACH }
+
+#[derive(Component)]
+pub struct Behaviour {
+    pub state: BehaviourState,
+    pub home: Option<Vec2>,
+    pub wander_radius: f32,
+    pub target: Option<Vec2>,
+    pub timer: Timer,
+    // offsets from home, in bevy's orientation
+    pub schedule: Vec<Vec2>,
+    pub schedule_index: usize,
+    // hour each schedule point is due at, without them the points are visited in turn
+    pub schedule_hours: Vec<f32>,
+    pub reaction: PlayerReaction,
+    pub reaction_distance: f32,
+}
+
+impl Behaviour {
+    pub fn enter(&mut self, state: BehaviourState, target: Option<Vec2>, seconds: f32) {
+        self.state = state;
+        self.target = target;
+        self.timer = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+
+    // the schedule point due last, yesterday's last one before the first of the day
+    pub fn scheduled_index(&self, hour: f32) -> Option<usize> {
+        let latest = |until: f32| self.schedule_hours.iter().enumerate()
+            .filter(|(_, due)| **due <= until)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index);
+        latest(hour).or_else(|| latest(f32::INFINITY))
+    }
+}
+
+impl Default for Behaviour {
+    fn default() -> Self {
+        Behaviour {
+            state: BehaviourState::IDLE,
+            home: None,
+            wander_radius: DEFAULT_WANDER_RADIUS,
+            target: None,
+            timer: Timer::from_seconds(IDLE_MIN_SECONDS, TimerMode::Once),
+            schedule: Vec::new(),
+            schedule_index: 0,
+            schedule_hours: Vec::new(),
+            reaction: PlayerReaction::NONE,
+            reaction_distance: DEFAULT_REACTION_DISTANCE,
+        }
+    }
+}
+
+impl From<EntityInstance> for Behaviour {
+    fn from(entity_instance: EntityInstance) -> Behaviour {
+        // LDtk points are grid cells counted downwards from the top of the level
+        let schedule: Vec<Vec2> = entity_points(&entity_instance, SCHEDULE_FIELD).iter()
+            .map(|point| {
+                let center = (point.as_vec2() + Vec2::splat(0.5)) * LEVEL_GRID_SIZE;
+                let offset = center - entity_instance.px.as_vec2();
+                Vec2::new(offset.x, -offset.y)
+            })
+            .collect();
+        let mut schedule_hours = entity_floats(&entity_instance, SCHEDULE_HOURS_FIELD);
+        if !schedule_hours.is_empty() && schedule_hours.len() != schedule.len() {
+            warn!(
+                "{} {} has {} {} for {} {} points, visiting them in turn", entity_instance.identifier, entity_instance.iid,
+                schedule_hours.len(), SCHEDULE_HOURS_FIELD, schedule.len(), SCHEDULE_FIELD
+            );
+            schedule_hours.clear();
+        }
+        let reaction = match entity_string(&entity_instance, PLAYER_REACTION_FIELD).as_deref() {
+            Some("Flee") => PlayerReaction::FLEE,
+            Some("Approach") => PlayerReaction::APPROACH,
+            Some("None") | None => PlayerReaction::NONE,
+            Some(reaction) => {
+                warn!("Unknown {} {} on {}", PLAYER_REACTION_FIELD, reaction, entity_instance.identifier);
+                PlayerReaction::NONE
+            }
+        };
+        let behaviour = Behaviour {..default()};
+        Behaviour {
+            wander_radius: entity_float(&entity_instance, WANDER_RADIUS_FIELD).unwrap_or(behaviour.wander_radius),
+            reaction_distance: entity_float(&entity_instance, REACTION_DISTANCE_FIELD).unwrap_or(behaviour.reaction_distance),
+            schedule,
+            schedule_hours,
+            reaction,
+            ..behaviour
+        }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct VillagerBundle {
+    pub villager: Villager,
+
+    #[from_entity_instance]
+    pub behaviour: Behaviour,
+
+    #[from_entity_instance]
+    pub interactable: Interactable,
+
+    #[ldtk_entity]
+    #[bundle]
+    pub person: PersonBundle,
+}
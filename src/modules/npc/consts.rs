@@ -0,0 +1,11 @@
+pub const DEFAULT_WANDER_RADIUS: f32 = 48.;
+pub const DEFAULT_REACTION_DISTANCE: f32 = 40.;
+
+pub const ARRIVAL_DISTANCE: f32 = 3.;
+pub const APPROACH_STOP_DISTANCE: f32 = 20.;
+
+pub const IDLE_MIN_SECONDS: f32 = 1.;
+pub const IDLE_MAX_SECONDS: f32 = 4.;
+pub const SCHEDULE_WAIT_SECONDS: f32 = 3.;
+// villagers stuck behind a collider give up on their target after this long
+pub const MOVE_TIMEOUT_SECONDS: f32 = 6.;
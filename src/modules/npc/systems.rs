@@ -0,0 +1,106 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, GlobalTransform, Query, Res, ResMut, Time, Transform, With, Without};
+use rand::Rng;
+use crate::modules::clock::components::WorldClock;
+use crate::modules::living::components::Player;
+use crate::modules::npc::components::{Behaviour, BehaviourState, PlayerReaction};
+use crate::modules::npc::consts::{APPROACH_STOP_DISTANCE, ARRIVAL_DISTANCE, IDLE_MAX_SECONDS, IDLE_MIN_SECONDS, MOVE_TIMEOUT_SECONDS, SCHEDULE_WAIT_SECONDS};
+use crate::modules::navigation::components::{NavGrid, NavPath, PathRequests};
+use crate::modules::navigation::systems::steer;
+use crate::modules::physics::components::SelfPhysical;
+use crate::modules::rng::components::{RngResource, RngStream};
+
+#[allow(clippy::type_complexity)]
+pub fn update_behaviours(
+    time: Res<Time>, clock: Res<WorldClock>, mut rng: ResMut<RngResource>, grid: Res<NavGrid>,
+    mut requests: ResMut<PathRequests>, players: Query<&GlobalTransform, With<Player>>,
+    mut npcs: Query<(Entity, &mut Behaviour, &mut SelfPhysical, &Transform, &GlobalTransform, Option<&mut NavPath>), Without<Player>>
+) {
+    let player_position = players.get_single().ok().map(|player| player.translation().truncate());
+    for (entity, mut behaviour, mut self_physical, transform, global_transform, mut nav_path) in npcs.iter_mut() {
+        // targets are kept in the level's space, the player and paths live in world space
+        let position = transform.translation.truncate();
+        let world_offset = global_transform.translation().truncate() - position;
+        let mut steer_to = |target: Vec2| steer(
+            entity, position + world_offset, target + world_offset, nav_path.as_deref_mut(), &grid, &mut requests
+        );
+        let home = *behaviour.home.get_or_insert(position);
+        let player_offset = player_position
+            .map(|player_position| player_position - global_transform.translation().truncate())
+            .filter(|offset| offset.length() < behaviour.reaction_distance);
+
+        let direction = match (behaviour.reaction, player_offset) {
+            (PlayerReaction::FLEE, Some(offset)) => {
+                behaviour.state = BehaviourState::FLEE;
+                -offset.normalize_or_zero()
+            }
+            (PlayerReaction::APPROACH, Some(offset)) => {
+                behaviour.state = BehaviourState::APPROACH;
+                if offset.length() > APPROACH_STOP_DISTANCE {
+                    offset.normalize_or_zero()
+                } else {
+                    Vec2::ZERO
+                }
+            }
+            _ => routine_direction(&mut behaviour, &time, &clock, &mut rng, position, home, &mut steer_to)
+        };
+
+        let direction = direction.extend(0.);
+        if self_physical.direction != direction {
+            self_physical.direction = direction;
+        }
+    }
+}
+
+fn routine_direction(
+    behaviour: &mut Behaviour, time: &Time, clock: &WorldClock, rng: &mut RngResource, position: Vec2, home: Vec2,
+    steer_to: &mut dyn FnMut(Vec2) -> Option<Vec2>
+) -> Vec2 {
+    let rng = rng.stream(RngStream::AI);
+    match behaviour.state {
+        BehaviourState::FLEE | BehaviourState::APPROACH => {
+            behaviour.enter(BehaviourState::IDLE, None, rng.gen_range(IDLE_MIN_SECONDS..IDLE_MAX_SECONDS));
+            Vec2::ZERO
+        }
+        BehaviourState::IDLE => {
+            if behaviour.timer.tick(time.delta()).finished() {
+                // timed schedules go on through the night, everyone else heads home
+                if let Some(index) = behaviour.scheduled_index(clock.hour()) {
+                    behaviour.schedule_index = index;
+                    let target = home + behaviour.schedule[index];
+                    behaviour.enter(BehaviourState::SCHEDULE, Some(target), MOVE_TIMEOUT_SECONDS);
+                } else if clock.is_night() {
+                    behaviour.enter(BehaviourState::REST, Some(home), MOVE_TIMEOUT_SECONDS);
+                } else if behaviour.schedule.is_empty() {
+                    let angle = rng.gen_range(0. ..std::f32::consts::TAU);
+                    let distance = rng.gen_range(0. ..=behaviour.wander_radius);
+                    let target = home + Vec2::new(angle.cos(), angle.sin()) * distance;
+                    behaviour.enter(BehaviourState::WANDER, Some(target), MOVE_TIMEOUT_SECONDS);
+                } else {
+                    let target = home + behaviour.schedule[behaviour.schedule_index];
+                    behaviour.enter(BehaviourState::SCHEDULE, Some(target), MOVE_TIMEOUT_SECONDS);
+                }
+            }
+            Vec2::ZERO
+        }
+        BehaviourState::WANDER | BehaviourState::SCHEDULE | BehaviourState::REST => {
+            let target = behaviour.target.unwrap_or(position);
+            let timed_out = behaviour.timer.tick(time.delta()).finished();
+            if target.distance(position) > ARRIVAL_DISTANCE && !timed_out {
+                // unreachable targets are given up like timed out ones
+                if let Some(direction) = steer_to(target) {
+                    return direction;
+                }
+            }
+            if behaviour.state == BehaviourState::SCHEDULE {
+                if behaviour.schedule_hours.is_empty() {
+                    behaviour.schedule_index = (behaviour.schedule_index + 1) % behaviour.schedule.len();
+                }
+                behaviour.enter(BehaviourState::IDLE, None, SCHEDULE_WAIT_SECONDS);
+            } else {
+                behaviour.enter(BehaviourState::IDLE, None, rng.gen_range(IDLE_MIN_SECONDS..IDLE_MAX_SECONDS));
+            }
+            Vec2::ZERO
+        }
+    }
+}
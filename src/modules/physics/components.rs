@@ -2,8 +2,10 @@ use bevy::ecs::component::Component;
 use bevy::ecs::bundle::Bundle;
 use bevy::math::{Vec2, Vec3};
 use bevy_inspector_egui::Inspectable;
-use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
-use crate::default;
+use bevy_ecs_ldtk::{EntityInstance, IntGridCell, LdtkEntity, LdtkIntCell};
+use bevy::utils::default;
+use crate::modules::level::consts::{COLLIDER_FIELD_PREFIX, LEVEL_GRID_SIZE, SPEED_FIELD, WEIGHT_FIELD};
+use crate::modules::level::fields::entity_float;
 
 #[derive(Default, Component, Inspectable)]
 pub struct Physical {
@@ -14,9 +16,9 @@ pub struct Physical {
 
 impl From<EntityInstance> for Physical {
     fn from(entity_instance: EntityInstance) -> Physical {
-        match entity_instance.identifier.as_str() {
-            "Player" => Physical { weight: 2.5, ..default() },
-            _ => Physical {..default()}
+        Physical {
+            weight: entity_float(&entity_instance, WEIGHT_FIELD).unwrap_or_default(),
+            ..default()
         }
     }
 }
@@ -27,6 +29,20 @@ pub struct TransformZone {
     pub offset: Vec2
 }
 
+impl TransformZone {
+    pub fn with_fields(self, entity_instance: &EntityInstance, prefix: &str) -> TransformZone {
+        let field = |name: &str| entity_float(entity_instance, &format!("{}_{}", prefix, name));
+        TransformZone {
+            size: Vec2::new(
+                field("Width").unwrap_or(self.size.x), field("Height").unwrap_or(self.size.y)
+            ),
+            offset: Vec2::new(
+                field("Offset_X").unwrap_or(self.offset.x), field("Offset_Y").unwrap_or(self.offset.y)
+            ),
+        }
+    }
+}
+
 #[derive(Component, Inspectable)]
 pub struct Collider(pub TransformZone);
 
@@ -38,14 +54,23 @@ impl Default for Collider {
 
 impl From<EntityInstance> for Collider {
     fn from(entity_instance: EntityInstance) -> Collider {
-        match entity_instance.identifier.as_str() {
-            "Player" => Collider(TransformZone {size: Vec2::new(16., 4.5), offset: Vec2::new(0., -8.)}),
-            "Rock" => Collider(TransformZone {size: Vec2::new(28., 20.), offset: Vec2::new(-6., -8.)}),
-            _ => Collider {..default()}
-        }
+        Collider(Collider::default().0.with_fields(&entity_instance, COLLIDER_FIELD_PREFIX))
     }
 }
 
+// a blocked cell collides over all of its area, IntGrid cells sit at their centers
+impl From<IntGridCell> for Collider {
+    fn from(_: IntGridCell) -> Collider {
+        Collider(TransformZone {size: Vec2::splat(LEVEL_GRID_SIZE), offset: Vec2::splat(-LEVEL_GRID_SIZE / 2.)})
+    }
+}
+
+#[derive(Bundle, Default, LdtkIntCell)]
+pub struct BlockedCellBundle {
+    #[from_int_grid_cell]
+    pub collider: Collider,
+}
+
 #[derive(Component, Inspectable)]
 pub struct SelfPhysical {
     pub direction: Vec3,
@@ -55,9 +80,10 @@ pub struct SelfPhysical {
 
 impl From<EntityInstance> for SelfPhysical {
     fn from(entity_instance: EntityInstance) -> SelfPhysical {
-        match entity_instance.identifier.as_str() {
-            "Player" => SelfPhysical { speed: 50., ..default() },
-            _ => SelfPhysical {..default()}
+        let self_physical = SelfPhysical::default();
+        SelfPhysical {
+            speed: entity_float(&entity_instance, SPEED_FIELD).unwrap_or(self_physical.speed),
+            ..self_physical
         }
     }
 }
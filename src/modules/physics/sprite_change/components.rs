@@ -2,29 +2,29 @@ use bevy::ecs::component::Component;
 use bevy::ecs::prelude::Resource;
 use bevy::math::Vec2;
 use bevy::prelude::{Reflect, ReflectComponent, TimerMode};
+use bevy::time::Timer;
 use bevy_ecs_ldtk::EntityInstance;
-use crate::{default, Timer};
+use crate::modules::level::consts::SPRITE_ZONE_FIELD_PREFIX;
 use crate::modules::physics::components::TransformZone;
 use crate::modules::physics::sprite_change::consts::MOB_BOTTOM_IDLE_START;
 use bevy_inspector_egui::Inspectable;
 
-#[derive(Inspectable, Debug)]
-pub enum Side { BOTTOM, LEFT, RIGHT, TOP }
+#[derive(Default, Inspectable, Debug, PartialEq, Eq)]
+pub enum Side { #[default] BOTTOM, LEFT, RIGHT, TOP }
 
-impl Default for Side {
-    fn default() -> Self {
-        Side::BOTTOM
+impl Side {
+    pub fn direction(&self) -> Vec2 {
+        match self {
+            Side::BOTTOM => Vec2::new(0., -1.),
+            Side::LEFT => Vec2::new(-1., 0.),
+            Side::RIGHT => Vec2::new(1., 0.),
+            Side::TOP => Vec2::new(0., 1.),
+        }
     }
 }
 
-#[derive(Inspectable, Debug, Reflect)]
-pub enum MovementState { IDLE, WALK, DRAG }
-
-impl Default for MovementState {
-    fn default() -> Self {
-        MovementState::IDLE
-    }
-}
+#[derive(Default, Inspectable, Debug, Reflect)]
+pub enum MovementState { #[default] IDLE, WALK, DRAG }
 
 #[derive(Default, Component, Inspectable)]
 pub struct MultipleSided {
@@ -50,6 +50,10 @@ impl Default for MultipleMovementState {
     }
 }
 
+// set while something else, like an AI action, drives the sprite frames
+#[derive(Default, Component)]
+pub struct AnimationOverride;
+
 #[derive(Default, Resource)]
 pub struct MovementSpriteTimer {
     pub timer: Timer
@@ -66,10 +70,6 @@ impl Default for SpriteZone {
 
 impl From<EntityInstance> for SpriteZone {
     fn from(entity_instance: EntityInstance) -> SpriteZone {
-        match entity_instance.identifier.as_str() {
-            "Player" => SpriteZone(TransformZone {size: Vec2::new(16., 32.), offset: Vec2::new(0., -8.)}),
-            "Rock" => SpriteZone(TransformZone {size: Vec2::new(32., 32.), offset: Vec2::new(-6., -8.)}),
-            _ => SpriteZone {..default()}
-        }
+        SpriteZone(SpriteZone::default().0.with_fields(&entity_instance, SPRITE_ZONE_FIELD_PREFIX))
     }
 }
\ No newline at end of file
@@ -25,4 +25,14 @@ pub const MOB_SIDE_IDLE_START: usize = MOB_SPRITE_SHEET_COLS;
 pub const MOB_SIDE_IDLE_END: usize = MOB_SIDE_IDLE_START + 2;
 
 pub const MOB_SIDE_WALK_START: usize = MOB_SIDE_IDLE_END + 1;
-pub const MOB_SIDE_WALK_END: usize = MOB_SIDE_WALK_START + 1;
\ No newline at end of file
+pub const MOB_SIDE_WALK_END: usize = MOB_SIDE_WALK_START + 1;
+
+// hurt frames are the last standing frame and the step after it, played once
+pub const MOB_BOTTOM_HURT_START: usize = MOB_BOTTOM_IDLE_END;
+pub const MOB_BOTTOM_HURT_END: usize = MOB_BOTTOM_WALK_START;
+
+pub const MOB_TOP_HURT_START: usize = MOB_TOP_IDLE_END;
+pub const MOB_TOP_HURT_END: usize = MOB_TOP_WALK_START;
+
+pub const MOB_SIDE_HURT_START: usize = MOB_SIDE_WALK_START;
+pub const MOB_SIDE_HURT_END: usize = MOB_SIDE_WALK_END;
\ No newline at end of file
@@ -1,10 +1,10 @@
 use std::time::Duration;
-use bevy::math::Vec2;
-use bevy::prelude::{Changed, EventWriter, Mut, Query, Res, TextureAtlasSprite, Time, Transform, Without};
+use bevy::utils::HashMap;
+use bevy::prelude::{Changed, Entity, EventWriter, GlobalTransform, Mut, Query, Res, TextureAtlasSprite, Time, Transform, Without};
 use crate::modules::physics::components::SelfPhysical;
 use crate::modules::physics::sprite_change::consts::{GENERAL_BOTTOM, GENERAL_SIDE, GENERAL_TOP, MOB_BOTTOM_IDLE_END, MOB_BOTTOM_IDLE_START, MOB_BOTTOM_WALK_END, MOB_BOTTOM_WALK_START, MOB_SIDE_IDLE_END, MOB_SIDE_IDLE_START, MOB_SIDE_WALK_END, MOB_SIDE_WALK_START, MOB_TOP_IDLE_END, MOB_TOP_IDLE_START, MOB_TOP_WALK_END, MOB_TOP_WALK_START};
-use crate::modules::physics::sprite_change::components::{MovementState, MultipleMovementState, MultipleSided, Side, SpriteZone};
-use crate::modules::sound::components::{SoundEvent, SoundType};
+use crate::modules::physics::sprite_change::components::{AnimationOverride, MovementState, MultipleMovementState, MultipleSided, Side, SpriteZone};
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
 
 pub fn sync_self_physical_multiple_movement(
     mut entities: Query<(&mut MultipleMovementState, &SelfPhysical), Changed<SelfPhysical>>
@@ -20,10 +20,10 @@ pub fn sync_self_physical_multiple_movement(
 }
 
 pub fn update_movement_sided_sprite(
-    mut multiple_sideds: Query<(&MultipleSided, &mut TextureAtlasSprite, &mut MultipleMovementState)>,
+    mut multiple_sideds: Query<(Entity, &MultipleSided, &mut TextureAtlasSprite, &mut MultipleMovementState), Without<AnimationOverride>>,
     mut sound_event: EventWriter<SoundEvent>, time: Res<Time>
 ) {
-    for (multiple_sided, atlas_sprite, mut multiple_movement_state) in multiple_sideds.iter_mut() {
+    for (entity, multiple_sided, atlas_sprite, mut multiple_movement_state) in multiple_sideds.iter_mut() {
         let should_increase = multiple_movement_state.timer.tick(time.delta()).just_finished();
         match multiple_movement_state.state {
             MovementState::IDLE => {
@@ -56,25 +56,25 @@ pub fn update_movement_sided_sprite(
                         update_walk_sprite(
                             multiple_movement_state, atlas_sprite, MOB_TOP_WALK_START,
                             MOB_TOP_WALK_END, MOB_TOP_IDLE_START, should_increase,
-                            true, false, &mut sound_event
+                            true, false, entity, &mut sound_event
                         ),
                     Side::BOTTOM =>
                         update_walk_sprite(
                             multiple_movement_state, atlas_sprite, MOB_BOTTOM_WALK_START,
                             MOB_BOTTOM_WALK_END, MOB_BOTTOM_IDLE_START, should_increase,
-                            true, false, &mut sound_event
+                            true, false, entity, &mut sound_event
                         ),
                     Side::LEFT =>
                         update_walk_sprite(
                             multiple_movement_state, atlas_sprite, MOB_SIDE_WALK_START,
                             MOB_SIDE_WALK_END, MOB_SIDE_IDLE_START, should_increase,
-                            false, false, &mut sound_event
+                            false, false, entity, &mut sound_event
                         ),
                     Side::RIGHT =>
                         update_walk_sprite(
                             multiple_movement_state, atlas_sprite, MOB_SIDE_WALK_START,
                             MOB_SIDE_WALK_END, MOB_SIDE_IDLE_START, should_increase,
-                            false, true, &mut sound_event
+                            false, true, entity, &mut sound_event
                         )
                 }
             }
@@ -82,10 +82,11 @@ pub fn update_movement_sided_sprite(
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn update_walk_sprite(
         mut multiple_movement_state: Mut<'_, MultipleMovementState>, mut atlas_sprite: Mut<'_, TextureAtlasSprite>,
         start_index: usize, end_index: usize, idle_index: usize, should_increase: bool, auto_flip_x: bool, flip_x: bool,
-        sound_event: &mut EventWriter<SoundEvent>
+        entity: Entity, sound_event: &mut EventWriter<SoundEvent>
     ) {
         if (multiple_movement_state.current_index < start_index || multiple_movement_state.current_index > end_index) &&
             multiple_movement_state.current_index != idle_index {
@@ -93,14 +94,14 @@ pub fn update_movement_sided_sprite(
             multiple_movement_state.used_first = true;
             if auto_flip_x {
                 atlas_sprite.flip_x = !atlas_sprite.flip_x;
-                sound_event.send(SoundEvent { path: "step/grass".to_string(), sound_type: SoundType::RANDOM, file: "4".to_string() });
+                sound_event.send(SoundEvent { path: "step/grass".to_string(), sound_type: SoundType::RANDOM, file: "4".to_string(), channel: SoundChannel::SFX, emitter: Some(entity) });
             }
         } else if should_increase {
             if multiple_movement_state.current_index == idle_index {
                 if auto_flip_x {
                     multiple_movement_state.current_index = start_index;
                     atlas_sprite.flip_x = !atlas_sprite.flip_x;
-                    sound_event.send(SoundEvent { path: "step/grass".to_string(), sound_type: SoundType::RANDOM, file: "4".to_string() });
+                    sound_event.send(SoundEvent { path: "step/grass".to_string(), sound_type: SoundType::RANDOM, file: "4".to_string(), channel: SoundChannel::SFX, emitter: Some(entity) });
 
                 } else {
                     if multiple_movement_state.used_first {
@@ -109,7 +110,7 @@ pub fn update_movement_sided_sprite(
                         multiple_movement_state.current_index = start_index;
                     }
                     multiple_movement_state.used_first = !multiple_movement_state.used_first;
-                    sound_event.send(SoundEvent { path: "step/grass".to_string(), sound_type: SoundType::RANDOM, file: "4".to_string() });
+                    sound_event.send(SoundEvent { path: "step/grass".to_string(), sound_type: SoundType::RANDOM, file: "4".to_string(), channel: SoundChannel::SFX, emitter: Some(entity) });
                 }
             } else {
                 multiple_movement_state.current_index = idle_index;
@@ -159,23 +160,15 @@ pub fn update_sided_sprite(mut multiple_sideds: Query<(&MultipleSided, &mut Text
     }
 }
 
-pub fn overlap_sprite_zones(mut sprite_zones: Query<(&SpriteZone, &mut Transform)>) {
-    let mut combinations = sprite_zones.iter_combinations_mut();
-    while let Some([(a_sprite_zone, mut a_transform), (b_sprite_zone, mut b_transform)]) = combinations.fetch_next() {
-        let a_sprite_zone_pos = Vec2 {
-            x: a_transform.translation.x + a_sprite_zone.0.offset.x,
-            y: a_transform.translation.y + a_sprite_zone.0.offset.y
-        };
-        let a_sprite_zone_len = Vec2::new(
-            a_sprite_zone_pos.x + a_sprite_zone.0.size.x, a_sprite_zone_pos.y + a_sprite_zone.0.size.y
-        );
-        let b_sprite_zone_pos = Vec2 {
-            x: b_transform.translation.x + b_sprite_zone.0.offset.x,
-            y: b_transform.translation.y + b_sprite_zone.0.offset.y
-        };
-        let b_sprite_zone_len = Vec2::new(
-            b_sprite_zone_pos.x + b_sprite_zone.0.size.x, b_sprite_zone_pos.y + b_sprite_zone.0.size.y
-        );
+pub fn overlap_sprite_zones(mut sprite_zones: Query<(Entity, &SpriteZone, &GlobalTransform, &mut Transform)>) {
+    let mut depths: HashMap<Entity, f32> = sprite_zones.iter()
+        .map(|(entity, _, global_transform, _)| (entity, global_transform.translation().z))
+        .collect();
+    for [(a_entity, a_sprite_zone, a_transform, _), (b_entity, b_sprite_zone, b_transform, _)] in sprite_zones.iter_combinations() {
+        let a_sprite_zone_pos = a_transform.translation().truncate() + a_sprite_zone.0.offset;
+        let a_sprite_zone_len = a_sprite_zone_pos + a_sprite_zone.0.size;
+        let b_sprite_zone_pos = b_transform.translation().truncate() + b_sprite_zone.0.offset;
+        let b_sprite_zone_len = b_sprite_zone_pos + b_sprite_zone.0.size;
         let collided = !(
             a_sprite_zone_len.y   <   b_sprite_zone_pos.y   ||
                 a_sprite_zone_pos.y   >   b_sprite_zone_len.y   ||
@@ -184,10 +177,18 @@ pub fn overlap_sprite_zones(mut sprite_zones: Query<(&SpriteZone, &mut Transform
         );
         if collided {
             if a_sprite_zone_pos.y < b_sprite_zone_pos.y {
-                a_transform.translation.z = b_transform.translation.z + 0.1;
+                depths.insert(a_entity, depths[&b_entity] + 0.1);
             } else {
-                b_transform.translation.z = a_transform.translation.z + 0.1;
+                depths.insert(b_entity, depths[&a_entity] + 0.1);
             }
         }
     }
-}
\ No newline at end of file
+
+    // depths are compared globally so entities from different levels sort together
+    for (entity, _, global_transform, mut transform) in sprite_zones.iter_mut() {
+        let depth_change = depths[&entity] - global_transform.translation().z;
+        if depth_change != 0. {
+            transform.translation.z += depth_change;
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use bevy::math::{Vec2, Vec3};
-use bevy::prelude::{Changed, Mut, Or, Query, Res, Time, Transform};
+use bevy::prelude::{Changed, GlobalTransform, Mut, Or, Query, Res, Time, Transform};
 use crate::modules::physics::components::{Collider, Physical, SelfPhysical};
 use crate::modules::physics::sprite_change::components::{MovementState, MultipleMovementState, MultipleSided, Side};
 
 
+#[allow(clippy::type_complexity)]
 pub fn update_movement_state_by_direction(
     mut entities: Query<(&mut MultipleMovementState, &Physical, Option<&SelfPhysical>), Or<(Changed<Physical>, Changed<SelfPhysical>)>>
 ) {
@@ -23,6 +24,7 @@ pub fn update_movement_state_by_direction(
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn update_sideds_by_direction(
     mut multiple_sideds: Query<(&Physical, &mut MultipleSided, Option<&SelfPhysical>), Or<(Changed<Physical>, Changed<SelfPhysical>)>>
 ) {
@@ -69,7 +71,7 @@ pub fn direction_react(time: Res<Time>, mut entities: Query<(&mut Physical, Opti
     }
 }
 
-pub fn collider_direction_react(mut colliders: Query<(Option<&mut Physical>, Option<&mut SelfPhysical>, &mut Transform, &Collider)>) {
+pub fn collider_direction_react(mut colliders: Query<(Option<&mut Physical>, Option<&mut SelfPhysical>, &GlobalTransform, &Collider)>) {
     let mut combinations = colliders.iter_combinations_mut();
     while let Some([(physical, self_physical, transform, collider), (target_physical, target_self_physical, target_transform, target_collider)]) = combinations.fetch_next() {
         if let Some(physical) = physical {
@@ -88,42 +90,39 @@ pub fn collider_direction_react(mut colliders: Query<(Option<&mut Physical>, Opt
     }
 
     fn collide_self(mut physical: Mut<'_, Physical, >, mut self_physical: Mut<'_, SelfPhysical, >,
-                    collider: &Collider, transform: Mut<'_, Transform, >,
-                    target_transform: Mut<'_, Transform, >, target_collider: &Collider) {
+                    collider: &Collider, transform: &GlobalTransform,
+                    target_transform: &GlobalTransform, target_collider: &Collider) {
         let direction = physical.direction.normalize_or_zero() + self_physical.direction.normalize_or_zero() * self_physical.multiplier;
-        let collider_pos = Vec2 {x: transform.translation.x + collider.0.offset.x, y: transform.translation.y + collider.0.offset.y};
+        let collider_pos = transform.translation().truncate() + collider.0.offset;
         let future_collider_pos_x = Vec2::new(direction.x + collider_pos.x, collider_pos.y);
-        if check_future(future_collider_pos_x, collider, &target_transform, target_collider) {
+        if check_future(future_collider_pos_x, collider, target_transform, target_collider) {
             physical.direction.x = 0.;
             self_physical.direction.x = 0.;
         }
         let future_collider_pos_y = Vec2::new(collider_pos.x, direction.y + collider_pos.y);
-        if check_future(future_collider_pos_y, collider, &target_transform, target_collider) {
+        if check_future(future_collider_pos_y, collider, target_transform, target_collider) {
             physical.direction.y = 0.;
             self_physical.direction.y = 0.;
         }
     }
 
-    fn collide(mut physical: Mut<'_, Physical, >, collider: &Collider, transform: Mut<'_, Transform, >,
-               target_transform: Mut<'_, Transform, >, target_collider: &Collider) {
-        let collider_pos = Vec2 {x: transform.translation.x + collider.0.offset.x, y: transform.translation.y + collider.0.offset.y};
+    fn collide(mut physical: Mut<'_, Physical, >, collider: &Collider, transform: &GlobalTransform,
+               target_transform: &GlobalTransform, target_collider: &Collider) {
+        let collider_pos = transform.translation().truncate() + collider.0.offset;
         let future_collider_pos_x = Vec2::new(physical.direction.x + collider_pos.x, collider_pos.y);
-        if check_future(future_collider_pos_x, collider, &target_transform, target_collider) {
+        if check_future(future_collider_pos_x, collider, target_transform, target_collider) {
             physical.direction.x = 0.;
         }
         let future_collider_pos_y = Vec2::new(collider_pos.x, physical.direction.y + collider_pos.y);
-        if check_future(future_collider_pos_y, collider, &target_transform, target_collider) {
+        if check_future(future_collider_pos_y, collider, target_transform, target_collider) {
             physical.direction.y = 0.;
         }
     }
 
     fn check_future(future_collider_pos: Vec2, collider: &Collider,
-                    target_transform: &Mut<'_, Transform, >, target_collider: &Collider) -> bool {
+                    target_transform: &GlobalTransform, target_collider: &Collider) -> bool {
         if future_collider_pos.x != 0. || future_collider_pos.y != 0. {
-            let target_collider_pos = Vec2 {
-                x: target_transform.translation.x + target_collider.0.offset.x,
-                y: target_transform.translation.y + target_collider.0.offset.y
-            };
+            let target_collider_pos = target_transform.translation().truncate() + target_collider.0.offset;
             let target_collider_len = Vec2::new(
                 target_collider_pos.x + target_collider.0.size.x, target_collider_pos.y + target_collider.0.size.y
             );
@@ -135,6 +134,6 @@ pub fn collider_direction_react(mut colliders: Query<(Option<&mut Physical>, Opt
                     future_collider_pos.x   >   target_collider_len.x
             );
         }
-        return false;
+        false
     }
 }
\ No newline at end of file
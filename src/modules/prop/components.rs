@@ -3,6 +3,7 @@ use bevy::ecs::bundle::Bundle;
 use bevy::prelude::SpriteSheetBundle;
 use bevy_inspector_egui::Inspectable;
 use bevy_ecs_ldtk::LdtkEntity;
+use crate::modules::interaction::components::Interactable;
 use crate::modules::physics::components::{Collider};
 use crate::modules::physics::sprite_change::components::SpriteZone;
 
@@ -14,6 +15,9 @@ pub struct Prop;
 pub struct PropBundle {
     pub prop: Prop,
 
+    #[from_entity_instance]
+    pub interactable: Interactable,
+
     #[from_entity_instance]
     pub collider: Collider,
 
@@ -39,4 +43,25 @@ pub struct RockBundle {
     #[ldtk_entity]
     #[bundle]
     pub prop_bundle: PropBundle,
-}
\ No newline at end of file
+}
+
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct BushBundle {
+    #[ldtk_entity]
+    #[bundle]
+    pub prop_bundle: PropBundle,
+}
+
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct FloraBundle {
+    pub prop: Prop,
+
+    #[from_entity_instance]
+    pub sprite_zone: SpriteZone,
+
+    #[sprite_sheet_bundle]
+    #[bundle]
+    pub sprite: SpriteSheetBundle,
+}
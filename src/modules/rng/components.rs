@@ -1,15 +1,115 @@
-use rand::rngs::StdRng;
+use std::collections::BTreeMap;
+use std::env;
 use bevy::ecs::prelude::Resource;
+use bevy::log::{info, warn};
+use bevy::utils::HashMap;
 use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use crate::modules::rng::consts::{RNG_CONFIG_PATH, SEED_ARGUMENT};
+use crate::modules::storage::files::read_ron;
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RngStream { AUDIO, WORLDGEN, AI, LOOT }
+
+impl RngStream {
+    pub const ALL: [RngStream; 4] = [RngStream::AUDIO, RngStream::WORLDGEN, RngStream::AI, RngStream::LOOT];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RngStream::AUDIO => "audio",
+            RngStream::WORLDGEN => "worldgen",
+            RngStream::AI => "ai",
+            RngStream::LOOT => "loot",
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct RngConfig {
+    pub seed: Option<u64>,
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RngState {
+    pub seed: u64,
+    pub word_positions: BTreeMap<String, u64>,
+}
+
+// streams use ChaCha12 (the StdRng algorithm) because it exposes its position for saving
 #[derive(Resource)]
 pub struct RngResource {
-    pub generator: StdRng
+    pub seed: u64,
+    streams: HashMap<RngStream, ChaCha12Rng>,
+}
+
+impl RngResource {
+    pub fn from_seed(seed: u64) -> Self {
+        RngResource { seed, streams: HashMap::default() }
+    }
+
+    pub fn load() -> Self {
+        let seed = seed_from_args()
+            .or_else(|| read_ron::<RngConfig>(RNG_CONFIG_PATH).and_then(|config| config.seed))
+            .unwrap_or_else(rand::random);
+        info!("Using rng seed {}", seed);
+        RngResource::from_seed(seed)
+    }
+
+    pub fn from_state(state: &RngState) -> Self {
+        let mut rng = RngResource::from_seed(state.seed);
+        for stream in RngStream::ALL {
+            if let Some(word_position) = state.word_positions.get(stream.name()) {
+                rng.stream(stream).set_word_pos(*word_position as u128);
+            }
+        }
+        rng
+    }
+
+    pub fn state(&self) -> RngState {
+        RngState {
+            seed: self.seed,
+            word_positions: self.streams.iter()
+                .map(|(stream, generator)| (stream.name().to_string(), generator.get_word_pos() as u64))
+                .collect(),
+        }
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut ChaCha12Rng {
+        let seed = self.derive_seed(stream.name());
+        self.streams.entry(stream).or_insert_with(|| ChaCha12Rng::seed_from_u64(seed))
+    }
+
+    // FNV-1a of the name mixed with the master seed, stable across runs and builds
+    pub fn derive_seed(&self, name: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in name.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        let mut mixed = self.seed ^ hash;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d049bb133111eb);
+        mixed ^ (mixed >> 31)
+    }
 }
 
 impl Default for RngResource {
     fn default() -> Self {
-        RngResource {generator: StdRng::from_entropy()}
+        RngResource::from_seed(rand::random())
     }
 }
 
+fn seed_from_args() -> Option<u64> {
+    let mut args = env::args().skip_while(|arg| arg != SEED_ARGUMENT).skip(1);
+    let value = args.next()?;
+    match value.parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => {
+            warn!("Ignoring invalid seed {}", value);
+            None
+        }
+    }
+}
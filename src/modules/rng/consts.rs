@@ -0,0 +1,2 @@
+pub const RNG_CONFIG_PATH: &str = "config/rng.ron";
+pub const SEED_ARGUMENT: &str = "--seed";
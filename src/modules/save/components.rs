@@ -0,0 +1,51 @@
+use bevy::ecs::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use crate::modules::building::components::PlacedStructures;
+use crate::modules::clock::components::ClockTime;
+use crate::modules::crafting::components::KnownRecipes;
+use crate::modules::inventory::components::Inventory;
+use crate::modules::rng::components::RngState;
+use crate::modules::save::consts::{FIRST_MANUAL_SLOT, SAVE_DIRECTORY};
+use crate::modules::tech::components::Progress;
+use crate::modules::world_state::components::WorldState;
+
+pub struct SaveEvent {
+    pub slot: u8,
+}
+
+pub struct LoadEvent {
+    pub slot: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub level_iid: String,
+    pub player_position: (f32, f32),
+    pub world_state: WorldState,
+    pub rng: RngState,
+    pub playtime: f64,
+    pub inventory: Inventory,
+    pub known_recipes: KnownRecipes,
+    pub progress: Progress,
+    pub placed_structures: PlacedStructures,
+    pub clock: ClockTime,
+}
+
+pub fn slot_path(slot: u8) -> String {
+    format!("{}/slot_{}.ron", SAVE_DIRECTORY, slot)
+}
+
+#[derive(Resource)]
+pub struct ActiveSlot(pub u8);
+
+impl Default for ActiveSlot {
+    fn default() -> Self {
+        ActiveSlot(FIRST_MANUAL_SLOT)
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct Playtime {
+    pub seconds: f64,
+}
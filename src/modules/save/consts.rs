@@ -0,0 +1,6 @@
+pub const SAVE_VERSION: u32 = 6;
+pub const SAVE_DIRECTORY: &str = "saves";
+
+pub const AUTOSAVE_SLOT: u8 = 0;
+pub const FIRST_MANUAL_SLOT: u8 = 1;
+pub const LAST_MANUAL_SLOT: u8 = 3;
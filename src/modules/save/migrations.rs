@@ -0,0 +1,131 @@
+use ron::{Map, Value};
+use crate::modules::save::components::SaveData;
+use crate::modules::save::consts::SAVE_VERSION;
+
+// MIGRATIONS[n] upgrades a save from version n + 1 to version n + 2
+const MIGRATIONS: &[fn(&mut Map)] = &[add_inventory, add_known_recipes, add_progress, add_placed_structures, add_clock];
+
+// version 2 saves the player's inventory, older saves start out empty handed
+fn add_inventory(map: &mut Map) {
+    let mut inventory = Map::new();
+    inventory.insert(Value::String("slots".to_string()), Value::Seq(Vec::new()));
+    map.insert(Value::String("inventory".to_string()), Value::Map(inventory));
+}
+
+// version 3 saves learnt recipes, older saves only know the ones unlocked from the start
+fn add_known_recipes(map: &mut Map) {
+    let mut known_recipes = Map::new();
+    known_recipes.insert(Value::String("recipes".to_string()), Value::Seq(Vec::new()));
+    map.insert(Value::String("known_recipes".to_string()), Value::Map(known_recipes));
+}
+
+// version 4 saves era progress, older saves start from the first era and make up discoveries as they go
+fn add_progress(map: &mut Map) {
+    map.insert(Value::String("progress".to_string()), Value::Map(Map::new()));
+}
+
+// version 5 saves what the player built, older saves have nothing built yet
+fn add_placed_structures(map: &mut Map) {
+    let mut placed_structures = Map::new();
+    placed_structures.insert(Value::String("structures".to_string()), Value::Seq(Vec::new()));
+    map.insert(Value::String("placed_structures".to_string()), Value::Map(placed_structures));
+}
+
+// version 6 saves the time of day, older saves wake up on the first morning
+fn add_clock(map: &mut Map) {
+    map.insert(Value::String("clock".to_string()), Value::Map(Map::new()));
+}
+
+pub fn migrate(value: Value) -> Result<SaveData, String> {
+    let mut map = match value {
+        Value::Map(map) => map,
+        _ => return Err("save is not a struct".to_string())
+    };
+    let version_key = Value::String("version".to_string());
+    let version = match map.iter().find(|(key, _)| **key == version_key) {
+        Some((_, Value::Number(number))) => number.as_i64().unwrap_or(0) as u32,
+        _ => return Err("save has no version".to_string())
+    };
+    if version == 0 || version > SAVE_VERSION {
+        return Err(format!("unsupported save version {}", version));
+    }
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        migration(&mut map);
+    }
+    map.insert(version_key, Value::Number((SAVE_VERSION as u64).into()));
+    Value::Map(map).into_rust().map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::building::components::{PlacedStructure, PlacedStructures};
+    use crate::modules::clock::components::ClockTime;
+    use crate::modules::clock::consts::START_HOUR;
+    use crate::modules::crafting::components::KnownRecipes;
+    use crate::modules::inventory::components::{Inventory, ItemStack};
+    use crate::modules::rng::components::RngState;
+    use crate::modules::tech::components::Progress;
+    use crate::modules::world_state::components::WorldState;
+
+    // what the first release wrote, before anything the migrations add existed
+    const VERSION_1_SAVE: &str = r#"(
+        version: 1,
+        level_iid: "level-0",
+        player_position: (48.0, 112.0),
+        world_state: (entities: {"rock-0": (destroyed: true)}),
+        rng: (seed: 42, word_positions: {"worldgen": 7}),
+        playtime: 12.5,
+    )"#;
+
+    #[test]
+    fn version_1_saves_are_upgraded_with_empty_progress() {
+        let save = migrate(ron::from_str(VERSION_1_SAVE).unwrap()).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.level_iid, "level-0");
+        assert_eq!(save.player_position, (48., 112.));
+        assert!(save.world_state.get("rock-0").unwrap().destroyed);
+        assert_eq!(save.rng.seed, 42);
+        assert_eq!(save.rng.word_positions.get("worldgen"), Some(&7));
+        assert_eq!(save.playtime, 12.5);
+        assert!(save.inventory.slots.is_empty());
+        assert!(save.known_recipes.recipes.is_empty());
+        assert_eq!(save.progress.era, 0);
+        assert!(save.progress.discoveries.is_empty());
+        assert!(save.placed_structures.structures.is_empty());
+        assert_eq!((save.clock.day, save.clock.hour), (0, START_HOUR));
+    }
+
+    #[test]
+    fn current_saves_round_trip_unchanged() {
+        let mut world_state = WorldState::default();
+        world_state.destroy("rock-0");
+        let mut inventory = Inventory::with_slots(2);
+        inventory.slots[1] = Some(ItemStack { item: "Stone".to_string(), count: 3 });
+        let save = SaveData {
+            version: SAVE_VERSION,
+            level_iid: "level-1".to_string(),
+            player_position: (16., -8.),
+            world_state,
+            rng: RngState { seed: 7, ..Default::default() },
+            playtime: 99.,
+            inventory,
+            known_recipes: KnownRecipes { recipes: ["Stone_Axe".to_string()].into() },
+            progress: Progress { era: 1, discoveries: vec!["Fire".to_string()], ..Default::default() },
+            placed_structures: PlacedStructures { structures: vec![PlacedStructure {
+                structure: "Campfire".to_string(), level_iid: "level-1".to_string(), cell: (3, 4)
+            }] },
+            clock: ClockTime { day: 2, hour: 21.5 },
+        };
+        let loaded = migrate(ron::from_str(&ron::to_string(&save).unwrap()).unwrap()).unwrap();
+        assert_eq!(ron::to_string(&loaded).unwrap(), ron::to_string(&save).unwrap());
+    }
+
+    #[test]
+    fn saves_from_unknown_versions_are_refused() {
+        for version in [0, SAVE_VERSION + 1] {
+            let save = VERSION_1_SAVE.replace("version: 1", &format!("version: {}", version));
+            assert!(migrate(ron::from_str(&save).unwrap()).is_err());
+        }
+    }
+}
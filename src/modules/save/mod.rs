@@ -0,0 +1,4 @@
+pub mod components;
+pub mod consts;
+pub mod migrations;
+pub mod systems;
@@ -0,0 +1,126 @@
+use bevy::asset::{Assets, Handle};
+use bevy::input::Input;
+use bevy::log::{info, warn};
+use bevy::math::Vec2;
+use bevy::prelude::{EventReader, EventWriter, KeyCode, Query, Res, ResMut, Time, Transform, With};
+use bevy_ecs_ldtk::LevelSelection;
+use bevy_ecs_ldtk::prelude::LdtkAsset;
+use ron::Value;
+use crate::modules::building::components::PlacedStructures;
+use crate::modules::clock::components::WorldClock;
+use crate::modules::crafting::components::{KnownRecipes, RecipeBook};
+use crate::modules::inventory::components::Inventory;
+use crate::modules::level::components::LevelTransition;
+use crate::modules::living::components::Player;
+use crate::modules::rng::components::RngResource;
+use crate::modules::save::components::{ActiveSlot, LoadEvent, Playtime, SaveData, SaveEvent, slot_path};
+use crate::modules::save::consts::{FIRST_MANUAL_SLOT, LAST_MANUAL_SLOT, SAVE_VERSION};
+use crate::modules::save::migrations::migrate;
+use crate::modules::storage::files::{read_ron, write_ron};
+use crate::modules::tech::components::Progress;
+use crate::modules::world_state::components::WorldState;
+
+pub fn read_save_input(
+    keyboard_input: Res<Input<KeyCode>>, mut active_slot: ResMut<ActiveSlot>,
+    mut save_events: EventWriter<SaveEvent>, mut load_events: EventWriter<LoadEvent>
+) {
+    if keyboard_input.just_pressed(KeyCode::PageUp) && active_slot.0 < LAST_MANUAL_SLOT {
+        active_slot.0 += 1;
+        info!("Active save slot {}", active_slot.0);
+    }
+    if keyboard_input.just_pressed(KeyCode::PageDown) && active_slot.0 > FIRST_MANUAL_SLOT {
+        active_slot.0 -= 1;
+        info!("Active save slot {}", active_slot.0);
+    }
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        save_events.send(SaveEvent { slot: active_slot.0 });
+    }
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        load_events.send(LoadEvent { slot: active_slot.0 });
+    }
+}
+
+pub fn tick_playtime(time: Res<Time>, mut playtime: ResMut<Playtime>) {
+    playtime.seconds += time.delta_seconds_f64();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn save_game(
+    mut save_events: EventReader<SaveEvent>, level_selection: Res<LevelSelection>,
+    ldtk_worlds: Query<&Handle<LdtkAsset>>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    players: Query<(&Transform, &Inventory), With<Player>>, world_state: Res<WorldState>,
+    rng: Res<RngResource>, playtime: Res<Playtime>, known_recipes: Res<KnownRecipes>,
+    progress: Res<Progress>, placed_structures: Res<PlacedStructures>, clock: Res<WorldClock>
+) {
+    for ev in save_events.iter() {
+        let level = ldtk_worlds.iter()
+            .filter_map(|ldtk_handle| ldtk_assets.get(ldtk_handle))
+            .find_map(|ldtk_asset| ldtk_asset.get_level(&level_selection));
+        let (level, (player, inventory)) = match (level, players.get_single()) {
+            (Some(level), Ok(player)) => (level, player),
+            _ => {
+                warn!("Nothing to save in slot {} yet", ev.slot);
+                continue;
+            }
+        };
+        let save = SaveData {
+            version: SAVE_VERSION,
+            level_iid: level.iid.clone(),
+            player_position: (player.translation.x, player.translation.y),
+            world_state: world_state.clone(),
+            rng: rng.state(),
+            playtime: playtime.seconds,
+            inventory: inventory.clone(),
+            known_recipes: known_recipes.clone(),
+            progress: progress.clone(),
+            placed_structures: placed_structures.clone(),
+            clock: clock.time,
+        };
+        write_ron(&slot_path(ev.slot), &save);
+        info!("Saved slot {}", ev.slot);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn load_game(
+    mut load_events: EventReader<LoadEvent>, mut transition: ResMut<LevelTransition>,
+    mut world_state: ResMut<WorldState>, mut rng: ResMut<RngResource>, mut playtime: ResMut<Playtime>,
+    mut known_recipes: ResMut<KnownRecipes>, book: Res<RecipeBook>, mut progress: ResMut<Progress>,
+    mut placed_structures: ResMut<PlacedStructures>, mut clock: ResMut<WorldClock>, mut players: Query<&mut Inventory, With<Player>>
+) {
+    for ev in load_events.iter() {
+        if transition.is_active() {
+            warn!("Cannot load slot {} during a level transition", ev.slot);
+            continue;
+        }
+        let path = slot_path(ev.slot);
+        let value = match read_ron::<Value>(&path) {
+            Some(value) => value,
+            None => {
+                warn!("There is no save in slot {}", ev.slot);
+                continue;
+            }
+        };
+        let save = match migrate(value) {
+            Ok(save) => save,
+            Err(error) => {
+                warn!("Could not load {}: {}", path, error);
+                continue;
+            }
+        };
+        *world_state = save.world_state;
+        *rng = RngResource::from_state(&save.rng);
+        playtime.seconds = save.playtime;
+        known_recipes.restore(save.known_recipes, &book);
+        *progress = save.progress;
+        *placed_structures = save.placed_structures;
+        clock.restore(save.clock);
+        for mut inventory in players.iter_mut() {
+            inventory.restore(save.inventory.clone());
+        }
+        transition.start_load(
+            LevelSelection::Iid(save.level_iid), Vec2::new(save.player_position.0, save.player_position.1)
+        );
+        info!("Loaded slot {}", ev.slot);
+    }
+}
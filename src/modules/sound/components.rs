@@ -1,7 +1,143 @@
+use bevy::asset::Handle;
+use bevy::ecs::prelude::Resource;
+use bevy::prelude::Entity;
+use bevy_kira_audio::{AudioInstance, AudioSource, PlaybackState};
+use serde::{Deserialize, Serialize};
+use crate::modules::sound::consts::AUDIO_SETTINGS_PATH;
+use crate::modules::storage::files::read_ron;
+
 pub enum SoundType { RANDOM, FIXED }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SoundChannel { MUSIC, SFX, AMBIENCE, UI }
+
 pub struct SoundEvent {
     pub path: String,
     pub sound_type: SoundType,
     pub file: String,
-}
\ No newline at end of file
+    pub channel: SoundChannel,
+    pub emitter: Option<Entity>,
+}
+
+#[derive(Resource)]
+pub struct MusicChannel;
+
+#[derive(Resource)]
+pub struct SfxChannel;
+
+#[derive(Resource)]
+pub struct AmbienceChannel;
+
+#[derive(Resource)]
+pub struct UiChannel;
+
+pub struct PlayingSound {
+    pub cue: String,
+    pub channel: SoundChannel,
+    pub source: Handle<AudioSource>,
+    pub instance: Handle<AudioInstance>,
+    pub emitter: Option<Entity>,
+    // the instance only exists once the source has loaded and the channel played it
+    pub started: bool,
+}
+
+impl PlayingSound {
+    // a sound without an instance is still queued, unless it had one already or its source can't load
+    pub fn keep(&mut self, state: Option<&PlaybackState>, source_failed: bool) -> bool {
+        match state {
+            Some(state) => {
+                self.started = true;
+                !matches!(state, PlaybackState::Stopped)
+            }
+            None => !self.started && !source_failed
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct PlayingSounds {
+    pub sounds: Vec<PlayingSound>
+}
+
+impl PlayingSounds {
+    pub fn voices(&self, cue: &str) -> usize {
+        self.sounds.iter().filter(|sound| sound.cue == cue).count()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct ChannelSettings {
+    pub volume: f64,
+    pub muted: bool,
+}
+
+impl ChannelSettings {
+    pub fn effective_volume(&self) -> f64 {
+        if self.muted { 0. } else { self.volume }
+    }
+}
+
+impl Default for ChannelSettings {
+    fn default() -> Self {
+        ChannelSettings { volume: 1., muted: false }
+    }
+}
+
+#[derive(Default, Resource, Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: ChannelSettings,
+    pub music: ChannelSettings,
+    pub sfx: ChannelSettings,
+    pub ambience: ChannelSettings,
+    pub ui: ChannelSettings,
+}
+
+impl AudioSettings {
+    pub fn load() -> Self {
+        read_ron(AUDIO_SETTINGS_PATH).unwrap_or_default()
+    }
+
+    pub fn volume(&self, channel: SoundChannel) -> f64 {
+        let channel_settings = match channel {
+            SoundChannel::MUSIC => self.music,
+            SoundChannel::SFX => self.sfx,
+            SoundChannel::AMBIENCE => self.ambience,
+            SoundChannel::UI => self.ui,
+        };
+        self.master.effective_volume() * channel_settings.effective_volume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(cue: &str) -> PlayingSound {
+        PlayingSound {
+            cue: cue.to_string(), channel: SoundChannel::SFX, source: Handle::default(), instance: Handle::default(),
+            emitter: None, started: false
+        }
+    }
+
+    #[test]
+    fn sounds_without_an_instance_yet_stay_queued() {
+        let mut playing_sounds = PlayingSounds { sounds: vec![queued("step/grass"), queued("step/grass")] };
+        playing_sounds.sounds.retain_mut(|sound| sound.keep(None, false));
+        assert_eq!(playing_sounds.voices("step/grass"), 2);
+    }
+
+    #[test]
+    fn sounds_end_once_their_instance_stops_or_goes() {
+        let mut sound = queued("step/grass");
+        assert!(sound.keep(Some(&PlaybackState::Playing { position: 0. }), false));
+        assert!(!sound.keep(None, false));
+        let mut sound = queued("step/grass");
+        assert!(!sound.keep(Some(&PlaybackState::Stopped), false));
+    }
+
+    #[test]
+    fn sounds_whose_source_fails_to_load_are_dropped() {
+        assert!(!queued("step/missing").keep(None, true));
+    }
+}
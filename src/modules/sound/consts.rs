@@ -1,3 +1,11 @@
 #![allow(dead_code)]
 
-pub const STEP_GRASS_MAX: usize = 4;
\ No newline at end of file
+pub const STEP_GRASS_MAX: usize = 4;
+
+pub const SOUND_HEARING_DISTANCE: f32 = 160.;
+pub const SOUND_PANNING_DISTANCE: f32 = 96.;
+pub const SOUND_MAX_PANNING: f32 = 0.4;
+
+pub const MAX_VOICES_PER_CUE: usize = 4;
+
+pub const AUDIO_SETTINGS_PATH: &str = "config/audio.ron";
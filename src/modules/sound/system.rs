@@ -1,20 +1,119 @@
-use bevy::asset::AssetServer;
-use bevy::prelude::{EventReader, Res, ResMut};
-use bevy_kira_audio::{Audio, AudioControl};
+use bevy::asset::{Assets, AssetServer, Handle, LoadState};
+use bevy::ecs::prelude::Resource;
+use bevy::input::Input;
+use bevy::math::Vec3;
+use bevy::prelude::{EventReader, GlobalTransform, KeyCode, Query, Res, ResMut, With};
+use bevy_kira_audio::{AudioChannel, AudioControl, AudioInstance, AudioSource, AudioTween};
 use rand::Rng;
-use crate::modules::rng::components::RngResource;
-use crate::modules::sound::components::{SoundEvent, SoundType};
+use crate::modules::camera::components::CameraTarget;
+use crate::modules::music::components::MusicDucking;
+use crate::modules::rng::components::{RngResource, RngStream};
+use crate::modules::sound::components::{AmbienceChannel, AudioSettings, MusicChannel, PlayingSound, PlayingSounds, SfxChannel, SoundChannel, SoundEvent, SoundType, UiChannel};
+use crate::modules::sound::consts::{AUDIO_SETTINGS_PATH, MAX_VOICES_PER_CUE, SOUND_HEARING_DISTANCE, SOUND_MAX_PANNING, SOUND_PANNING_DISTANCE};
+use crate::modules::storage::files::write_ron;
 
+#[allow(clippy::too_many_arguments)]
 pub fn on_sound_emit(
-    mut rng: ResMut<RngResource>, audio: Res<Audio>, asset_server: Res<AssetServer>,
-    mut ev_sound: EventReader<SoundEvent>
+    mut rng: ResMut<RngResource>, asset_server: Res<AssetServer>, mut playing_sounds: ResMut<PlayingSounds>,
+    music: Res<AudioChannel<MusicChannel>>, sfx: Res<AudioChannel<SfxChannel>>,
+    ambience: Res<AudioChannel<AmbienceChannel>>, ui: Res<AudioChannel<UiChannel>>,
+    audio_instances: Res<Assets<AudioInstance>>, emitters: Query<&GlobalTransform>,
+    listeners: Query<&GlobalTransform, With<CameraTarget>>, mut ev_sound: EventReader<SoundEvent>
 ) {
+    playing_sounds.sounds.retain_mut(|sound| {
+        let state = audio_instances.get(&sound.instance).map(|instance| instance.state());
+        let source_failed = asset_server.get_load_state(&sound.source) == LoadState::Failed;
+        sound.keep(state.as_ref(), source_failed)
+    });
+    let listener = listeners.get_single().ok().map(|listener| listener.translation());
     for ev in ev_sound.iter() {
+        if playing_sounds.voices(&ev.path) >= MAX_VOICES_PER_CUE {
+            continue;
+        }
+
+        let mut volume = 1.;
+        let mut panning = 0.5;
+        if let (Some(emitter), Some(listener)) = (ev.emitter, listener) {
+            if let Ok(emitter_transform) = emitters.get(emitter) {
+                (volume, panning) = spatialize(emitter_transform.translation(), listener);
+                if volume <= 0. {
+                    continue;
+                }
+            }
+        }
+
         let sound_path = "sounds/".to_owned() + &*ev.path + "/" +
             &*match ev.sound_type {
-                SoundType::RANDOM => (rng.generator.gen::<u32>() % 4).to_string(),
+                SoundType::RANDOM => (rng.stream(RngStream::AUDIO).gen::<u32>() % 4).to_string(),
                 SoundType::FIXED => ev.file.to_string(),
             } +".wav";
-        audio.play(asset_server.load(sound_path));
+        let source: Handle<AudioSource> = asset_server.load(sound_path);
+        let instance = match ev.channel {
+            SoundChannel::MUSIC => play_on(&music, source.clone(), volume, panning),
+            SoundChannel::SFX => play_on(&sfx, source.clone(), volume, panning),
+            SoundChannel::AMBIENCE => play_on(&ambience, source.clone(), volume, panning),
+            SoundChannel::UI => play_on(&ui, source.clone(), volume, panning),
+        };
+        playing_sounds.sounds.push(PlayingSound {
+            cue: ev.path.clone(), channel: ev.channel, source, instance, emitter: ev.emitter, started: false
+        });
+    }
+
+    fn play_on<T: Resource>(
+        channel: &AudioChannel<T>, source: Handle<AudioSource>, volume: f64, panning: f64
+    ) -> Handle<AudioInstance> {
+        channel.play(source).with_volume(volume).with_panning(panning).handle()
+    }
+}
+
+pub fn update_positional_sounds(
+    playing_sounds: Res<PlayingSounds>, mut audio_instances: ResMut<Assets<AudioInstance>>,
+    emitters: Query<&GlobalTransform>, listeners: Query<&GlobalTransform, With<CameraTarget>>
+) {
+    let listener = match listeners.get_single() {
+        Ok(listener) => listener.translation(),
+        _ => return
+    };
+    for sound in playing_sounds.sounds.iter() {
+        let emitter_transform = match sound.emitter.and_then(|emitter| emitters.get(emitter).ok()) {
+            Some(emitter_transform) => emitter_transform,
+            None => continue
+        };
+        if let Some(instance) = audio_instances.get_mut(&sound.instance) {
+            let (volume, panning) = spatialize(emitter_transform.translation(), listener);
+            instance.set_volume(volume, AudioTween::default());
+            instance.set_panning(panning, AudioTween::default());
+        }
     }
-}
\ No newline at end of file
+}
+
+pub fn toggle_mute(keyboard_input: Res<Input<KeyCode>>, mut settings: ResMut<AudioSettings>) {
+    if keyboard_input.just_pressed(KeyCode::M) {
+        settings.master.muted = !settings.master.muted;
+    }
+}
+
+pub fn apply_audio_settings(
+    settings: Res<AudioSettings>, ducking: Res<MusicDucking>,
+    music: Res<AudioChannel<MusicChannel>>, sfx: Res<AudioChannel<SfxChannel>>,
+    ambience: Res<AudioChannel<AmbienceChannel>>, ui: Res<AudioChannel<UiChannel>>
+) {
+    if !settings.is_changed() && !ducking.is_changed() {
+        return;
+    }
+    music.set_volume(settings.volume(SoundChannel::MUSIC) * ducking.volume);
+    sfx.set_volume(settings.volume(SoundChannel::SFX));
+    ambience.set_volume(settings.volume(SoundChannel::AMBIENCE));
+    ui.set_volume(settings.volume(SoundChannel::UI));
+    if settings.is_changed() && !settings.is_added() {
+        write_ron(AUDIO_SETTINGS_PATH, &*settings);
+    }
+}
+
+// kira panning goes from 0. (left) to 1. (right)
+fn spatialize(emitter: Vec3, listener: Vec3) -> (f64, f64) {
+    let offset = emitter.truncate() - listener.truncate();
+    let closeness = (1. - offset.length() / SOUND_HEARING_DISTANCE).clamp(0., 1.);
+    let panning = 0.5 + (offset.x / SOUND_PANNING_DISTANCE).clamp(-1., 1.) * SOUND_MAX_PANNING;
+    ((closeness * closeness) as f64, panning as f64)
+}
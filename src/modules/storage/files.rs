@@ -0,0 +1,36 @@
+use std::fs;
+use std::path::Path;
+use bevy::log::warn;
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub fn read_ron<T: DeserializeOwned>(path: &str) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    match ron::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Could not parse {}: {}", path, error);
+            None
+        }
+    }
+}
+
+pub fn write_ron<T: Serialize>(path: &str, value: &T) {
+    if let Some(parent) = Path::new(path).parent() {
+        if let Err(error) = fs::create_dir_all(parent) {
+            warn!("Could not create {}: {}", parent.display(), error);
+            return;
+        }
+    }
+    let content = match ron::ser::to_string_pretty(value, PrettyConfig::default()) {
+        Ok(content) => content,
+        Err(error) => {
+            warn!("Could not serialize {}: {}", path, error);
+            return;
+        }
+    };
+    if let Err(error) = fs::write(path, content) {
+        warn!("Could not write {}: {}", path, error);
+    }
+}
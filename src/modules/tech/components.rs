@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::log::warn;
+use bevy::prelude::TimerMode;
+use bevy::sprite::TextureAtlas;
+use bevy::time::Timer;
+use bevy::utils::default;
+use serde::{Deserialize, Serialize};
+use crate::modules::building::components::StructureCatalog;
+use crate::modules::crafting::components::RecipeBook;
+use crate::modules::inventory::components::ItemDatabase;
+use crate::modules::storage::files::read_ron;
+use crate::modules::tech::consts::{NOTIFICATION_SECONDS, TECH_DATA_PATH};
+use crate::modules::tech::validation::{validate_discovery, validate_era};
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum Condition {
+    // (recipe, times) crafted in total
+    CRAFT(String, u32),
+    // (item, count) the player got in total, artifacts are items too
+    COLLECT(String, u32),
+    // identifier of a level the player has been in
+    REACH(String),
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Unlocks {
+    pub recipes: Vec<String>,
+    pub structures: Vec<String>,
+    // sheet laid out like the base one that every person wears from then on
+    pub person_sprite: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Discovery {
+    pub name: String,
+    // discoveries that have to be made first
+    #[serde(default)]
+    pub requires: Vec<String>,
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub unlocks: Unlocks,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Era {
+    pub name: String,
+    // an era is reached once these and the eras before it are
+    pub discoveries: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct TechData {
+    pub eras: Vec<Era>,
+    pub discoveries: HashMap<String, Discovery>,
+}
+
+// leaving one discovery out leaves out the ones requiring it, until nothing else goes
+fn drop_unreachable(discoveries: &mut HashMap<String, Discovery>) {
+    loop {
+        let kept: BTreeSet<String> = discoveries.keys().cloned().collect();
+        let count = discoveries.len();
+        discoveries.retain(|name, discovery| {
+            let missing: Vec<&String> = discovery.requires.iter().filter(|required| !kept.contains(*required)).collect();
+            for required in missing.iter() {
+                warn!("Discovery {} requires {} that was left out", name, required);
+            }
+            missing.is_empty()
+        });
+        if discoveries.len() == count {
+            break;
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct TechTree {
+    pub data: TechData,
+}
+
+impl TechTree {
+    // discoveries that don't match the items and recipes are left out
+    pub fn load(items: &ItemDatabase, book: &RecipeBook, structures: &StructureCatalog) -> Self {
+        let mut data: TechData = read_ron(TECH_DATA_PATH).unwrap_or_else(|| {
+            warn!("Could not load the tech tree from {}", TECH_DATA_PATH);
+            TechData {..default()}
+        });
+        let names: BTreeSet<String> = data.discoveries.keys().cloned().collect();
+        data.discoveries.retain(|name, discovery| {
+            let errors = validate_discovery(name, discovery, &names, items, book, structures);
+            for error in errors.iter() {
+                warn!("Discovery {} {}", name, error);
+            }
+            errors.is_empty()
+        });
+        drop_unreachable(&mut data.discoveries);
+        for era in data.eras.iter_mut() {
+            for error in validate_era(era, &data.discoveries) {
+                warn!("Era {} {}", era.name, error);
+            }
+            era.discoveries.retain(|discovery| data.discoveries.contains_key(discovery));
+        }
+        TechTree { data }
+    }
+
+    pub fn get(&self, discovery: &str) -> Option<&Discovery> {
+        self.data.discoveries.get(discovery)
+    }
+
+    pub fn era_name(&self, era: usize) -> &str {
+        self.data.eras.get(era).map_or("", |era| era.name.as_str())
+    }
+}
+
+#[derive(Clone, Default, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+    pub era: usize,
+    // in the order they were made
+    pub discoveries: Vec<String>,
+    pub crafted: BTreeMap<String, u32>,
+    pub collected: BTreeMap<String, u32>,
+    pub visited: BTreeSet<String>,
+}
+
+impl Progress {
+    pub fn has_discovered(&self, discovery: &str) -> bool {
+        self.discoveries.iter().any(|made| made == discovery)
+    }
+
+    pub fn met(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::CRAFT(recipe, times) => self.crafted.get(recipe).copied().unwrap_or(0) >= *times,
+            Condition::COLLECT(item, count) => self.collected.get(item).copied().unwrap_or(0) >= *count,
+            Condition::REACH(level) => self.visited.contains(level),
+        }
+    }
+
+    pub fn can_discover(&self, discovery: &Discovery) -> bool {
+        discovery.requires.iter().all(|required| self.has_discovered(required)) &&
+            discovery.conditions.iter().all(|condition| self.met(condition))
+    }
+
+    pub fn unlocks_structure(&self, tree: &TechTree, structure: &str) -> bool {
+        self.discoveries.iter()
+            .filter_map(|discovery| tree.get(discovery))
+            .any(|discovery| discovery.unlocks.structures.iter().any(|unlocked| unlocked == structure))
+    }
+
+    // the latest discovery that changes how people look wins
+    pub fn person_sprite<'a>(&self, tree: &'a TechTree) -> Option<&'a str> {
+        self.discoveries.iter().rev()
+            .filter_map(|discovery| tree.get(discovery))
+            .find_map(|discovery| discovery.unlocks.person_sprite.as_deref())
+    }
+}
+
+pub struct DiscoveryMade {
+    pub discovery: String,
+}
+
+pub struct EraReached {
+    pub era: usize,
+}
+
+// the atlas a person spawned with, and the sheet it wears instead
+#[derive(Component)]
+pub struct PersonSprite {
+    pub base: Handle<TextureAtlas>,
+    pub variant: Option<String>,
+}
+
+// atlases of every sheet, cut like the base atlas they replace
+#[derive(Default, Resource)]
+pub struct PersonSprites {
+    pub atlases: HashMap<(Handle<TextureAtlas>, String), Handle<TextureAtlas>>,
+}
+
+impl PersonSprites {
+    pub fn variant(
+        &mut self, base: &Handle<TextureAtlas>, path: &str, asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>
+    ) -> Option<Handle<TextureAtlas>> {
+        let key = (base.clone(), path.to_string());
+        if let Some(atlas) = self.atlases.get(&key) {
+            return Some(atlas.clone());
+        }
+        let base_atlas = texture_atlases.get(base)?;
+        let atlas = TextureAtlas {
+            texture: asset_server.load(path),
+            size: base_atlas.size,
+            textures: base_atlas.textures.clone(),
+            texture_handles: None,
+        };
+        let atlas = texture_atlases.add(atlas);
+        self.atlases.insert(key, atlas.clone());
+        Some(atlas)
+    }
+}
+
+// shown one after the other at the top of the screen
+#[derive(Resource)]
+pub struct Notifications {
+    pub pending: VecDeque<String>,
+    pub timer: Timer,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(NOTIFICATION_SECONDS, TimerMode::Once);
+        timer.tick(timer.duration());
+        Notifications { pending: VecDeque::new(), timer }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct NotificationText;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovery(requires: &[&str]) -> Discovery {
+        Discovery {
+            name: String::new(),
+            requires: requires.iter().map(|required| required.to_string()).collect(),
+            conditions: Vec::new(),
+            unlocks: Unlocks {..default()},
+        }
+    }
+
+    #[test]
+    fn requiring_a_left_out_discovery_leaves_out_the_whole_chain() {
+        let mut discoveries = HashMap::from([
+            ("fire".to_string(), discovery(&[])),
+            ("pottery".to_string(), discovery(&["fire", "clay"])),
+            ("kiln".to_string(), discovery(&["pottery"])),
+            ("glass".to_string(), discovery(&["kiln", "fire"])),
+            ("cooking".to_string(), discovery(&["fire"])),
+        ]);
+        drop_unreachable(&mut discoveries);
+        let kept: BTreeSet<&str> = discoveries.keys().map(String::as_str).collect();
+        assert_eq!(kept, BTreeSet::from(["cooking", "fire"]));
+    }
+}
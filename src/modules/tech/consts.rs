@@ -0,0 +1,5 @@
+pub const TECH_DATA_PATH: &str = "assets/data/eras.ron";
+
+pub const DISCOVERY_SOUND: &str = "ui/discovery";
+pub const NOTIFICATION_SECONDS: f32 = 3.;
+pub const NOTIFICATION_FONT_SIZE: f32 = 14.;
@@ -0,0 +1,187 @@
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::log::info;
+use bevy::prelude::{Commands, DetectChanges, Entity, EventReader, EventWriter, Query, Res, ResMut, Text, Time, Visibility, With, World};
+use bevy::sprite::TextureAtlas;
+use bevy_ecs_ldtk::LevelSelection;
+use bevy_ecs_ldtk::prelude::LdtkAsset;
+use crate::modules::building::components::StructureCatalog;
+use crate::modules::crafting::components::{Crafted, KnownRecipes, RecipeBook, RecipeUnlocked};
+use crate::modules::inventory::components::{InventoryChanged, ItemDatabase};
+use crate::modules::living::components::{Person, Player};
+use crate::modules::sound::components::{SoundChannel, SoundEvent, SoundType};
+use crate::modules::tech::components::{DiscoveryMade, EraReached, NotificationText, Notifications, PersonSprite, PersonSprites, Progress, TechTree};
+use crate::modules::tech::consts::DISCOVERY_SOUND;
+
+// a startup system rather than loaded up front, so the log plugin is there to print what the tree gets wrong
+pub fn load_tech_tree(world: &mut World) {
+    let tech_tree = TechTree::load(
+        world.resource::<ItemDatabase>(), world.resource::<RecipeBook>(), world.resource::<StructureCatalog>()
+    );
+    world.insert_resource(tech_tree);
+}
+
+pub fn track_progress(
+    mut progress: ResMut<Progress>, mut crafted_events: EventReader<Crafted>,
+    mut inventory_events: EventReader<InventoryChanged>, players: Query<(), With<Player>>,
+    level_selection: Res<LevelSelection>, ldtk_worlds: Query<&Handle<LdtkAsset>>, ldtk_assets: Res<Assets<LdtkAsset>>
+) {
+    for crafted_event in crafted_events.iter() {
+        *progress.crafted.entry(crafted_event.recipe.clone()).or_insert(0) += 1;
+    }
+    for inventory_event in inventory_events.iter() {
+        if inventory_event.delta > 0 && players.get(inventory_event.entity).is_ok() {
+            *progress.collected.entry(inventory_event.item.clone()).or_insert(0) += inventory_event.delta as u32;
+        }
+    }
+    let level = ldtk_worlds.iter()
+        .filter_map(|ldtk_handle| ldtk_assets.get(ldtk_handle))
+        .find_map(|ldtk_asset| ldtk_asset.get_level(&level_selection));
+    if let Some(level) = level {
+        if !progress.visited.contains(&level.identifier) {
+            progress.visited.insert(level.identifier.clone());
+        }
+    }
+}
+
+// discoveries can make others possible, so they are made until nothing changes
+pub fn make_discoveries(
+    tree: Res<TechTree>, book: Res<RecipeBook>, mut progress: ResMut<Progress>, mut known: ResMut<KnownRecipes>,
+    mut discovery_events: EventWriter<DiscoveryMade>, mut era_events: EventWriter<EraReached>,
+    mut unlocked_events: EventWriter<RecipeUnlocked>
+) {
+    if !progress.is_changed() {
+        return;
+    }
+    loop {
+        let next = tree.data.discoveries.iter()
+            .filter(|(name, discovery)| !progress.has_discovered(name) && progress.can_discover(discovery))
+            .map(|(name, _)| name)
+            .min();
+        let name = match next {
+            Some(name) => name,
+            None => break
+        };
+        progress.discoveries.push(name.clone());
+        if let Some(discovery) = tree.get(name) {
+            info!("Discovered {}", discovery.name);
+            for recipe in discovery.unlocks.recipes.iter() {
+                if book.get(recipe).is_some() && known.unlock(recipe) {
+                    unlocked_events.send(RecipeUnlocked { recipe: recipe.clone() });
+                }
+            }
+        }
+        discovery_events.send(DiscoveryMade { discovery: name.clone() });
+    }
+    while let Some(era) = tree.data.eras.get(progress.era + 1) {
+        if !era.discoveries.iter().all(|discovery| progress.has_discovered(discovery)) {
+            break;
+        }
+        progress.era += 1;
+        info!("Reached the {}", era.name);
+        era_events.send(EraReached { era: progress.era });
+    }
+}
+
+pub fn notify_progress(
+    tree: Res<TechTree>, book: Res<RecipeBook>, mut notifications: ResMut<Notifications>,
+    mut discovery_events: EventReader<DiscoveryMade>, mut era_events: EventReader<EraReached>,
+    mut unlocked_events: EventReader<RecipeUnlocked>, mut sound_events: EventWriter<SoundEvent>
+) {
+    let mut messages = Vec::new();
+    for era_event in era_events.iter() {
+        messages.push(format!("The {} has begun", tree.era_name(era_event.era)));
+    }
+    for discovery_event in discovery_events.iter() {
+        if let Some(discovery) = tree.get(&discovery_event.discovery) {
+            messages.push(format!("Discovered {}", discovery.name));
+        }
+    }
+    for unlocked_event in unlocked_events.iter() {
+        if let Some(recipe) = book.get(&unlocked_event.recipe) {
+            messages.push(format!("New recipe: {}", recipe.name));
+        }
+    }
+    if messages.is_empty() {
+        return;
+    }
+    notifications.pending.extend(messages);
+    sound_events.send(SoundEvent {
+        path: DISCOVERY_SOUND.to_string(),
+        sound_type: SoundType::RANDOM,
+        file: String::new(),
+        channel: SoundChannel::UI,
+        emitter: None,
+    });
+}
+
+pub fn update_notifications(
+    time: Res<Time>, mut notifications: ResMut<Notifications>,
+    mut notification_texts: Query<(&mut Text, &mut Visibility), With<NotificationText>>
+) {
+    let mut shown = None;
+    if notifications.timer.tick(time.delta()).finished() {
+        shown = notifications.pending.pop_front();
+        if shown.is_some() {
+            notifications.timer.reset();
+        }
+    }
+    let visible = !notifications.timer.finished();
+    for (mut text, mut visibility) in notification_texts.iter_mut() {
+        if let Some(shown) = &shown {
+            text.sections[0].value = shown.clone();
+        }
+        if visibility.is_visible != visible {
+            visibility.is_visible = visible;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn apply_person_sprites(
+    mut commands: Commands, tree: Res<TechTree>, progress: Res<Progress>, mut person_sprites: ResMut<PersonSprites>,
+    asset_server: Res<AssetServer>, mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut persons: Query<(Entity, &mut Handle<TextureAtlas>, Option<&mut PersonSprite>), With<Person>>
+) {
+    let variant = progress.person_sprite(&tree);
+    for (entity, mut texture_atlas, person_sprite) in persons.iter_mut() {
+        let base = match &person_sprite {
+            Some(person_sprite) if person_sprite.variant.as_deref() == variant => continue,
+            Some(person_sprite) => person_sprite.base.clone(),
+            None => texture_atlas.clone()
+        };
+        let atlas = match variant {
+            Some(path) => match person_sprites.variant(&base, path, &asset_server, &mut texture_atlases) {
+                Some(atlas) => atlas,
+                None => continue
+            },
+            None => base.clone()
+        };
+        *texture_atlas = atlas;
+        let variant = variant.map(str::to_string);
+        match person_sprite {
+            Some(mut person_sprite) => person_sprite.variant = variant,
+            None => {
+                commands.entity(entity).insert(PersonSprite { base, variant });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, StartupStage};
+    use crate::modules::testing::logs::capture_warnings;
+    use super::*;
+
+    #[test]
+    fn loading_the_tree_warns_about_what_it_leaves_out() {
+        let mut app = App::new();
+        app.insert_resource(ItemDatabase::default())
+            .insert_resource(RecipeBook::default())
+            .insert_resource(StructureCatalog::default())
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_tech_tree);
+        let warnings = capture_warnings(|| app.update());
+        assert!(warnings.contains("Discovery "), "{}", warnings);
+        assert!(app.world.contains_resource::<TechTree>());
+    }
+}
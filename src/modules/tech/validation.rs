@@ -0,0 +1,52 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::modules::building::components::StructureCatalog;
+use crate::modules::crafting::components::RecipeBook;
+use crate::modules::inventory::components::ItemDatabase;
+use crate::modules::tech::components::{Condition, Discovery, Era};
+
+pub fn validate_discovery(
+    name: &str, discovery: &Discovery, discoveries: &BTreeSet<String>, items: &ItemDatabase, book: &RecipeBook,
+    structures: &StructureCatalog
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for required in discovery.requires.iter() {
+        if required == name {
+            errors.push("requires itself".to_string());
+        } else if !discoveries.contains(required) {
+            errors.push(format!("requires unknown discovery {}", required));
+        }
+    }
+    for condition in discovery.conditions.iter() {
+        match condition {
+            Condition::CRAFT(recipe, _) if book.get(recipe).is_none() => {
+                errors.push(format!("needs crafting unknown recipe {}", recipe));
+            }
+            Condition::COLLECT(item, _) if items.get(item).is_none() => {
+                errors.push(format!("needs collecting unknown item {}", item));
+            }
+            _ => {}
+        }
+    }
+    for recipe in discovery.unlocks.recipes.iter() {
+        match book.get(recipe) {
+            Some(definition) if !definition.locked => errors.push(format!("unlocks recipe {} that is never locked", recipe)),
+            None => errors.push(format!("unlocks unknown recipe {}", recipe)),
+            _ => {}
+        }
+    }
+    for structure in discovery.unlocks.structures.iter() {
+        match structures.get(structure) {
+            Some(definition) if !definition.locked => errors.push(format!("unlocks structure {} that is never locked", structure)),
+            None => errors.push(format!("unlocks unknown structure {}", structure)),
+            _ => {}
+        }
+    }
+    errors
+}
+
+pub fn validate_era(era: &Era, discoveries: &HashMap<String, Discovery>) -> Vec<String> {
+    era.discoveries.iter()
+        .filter(|discovery| !discoveries.contains_key(*discovery))
+        .map(|discovery| format!("needs unknown discovery {}", discovery))
+        .collect()
+}
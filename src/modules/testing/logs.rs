@@ -0,0 +1,39 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use bevy::utils::tracing::{subscriber, Level};
+use tracing_subscriber::fmt::MakeWriter;
+
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for CapturedLogs {
+    type Writer = CapturedLogs;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+// what a run prints at warning level, the way the app's log plugin would print it
+pub fn capture_warnings(run: impl FnOnce()) -> String {
+    let logs = CapturedLogs::default();
+    let capture = tracing_subscriber::fmt()
+        .with_writer(logs.clone())
+        .with_max_level(Level::WARN)
+        .with_ansi(false)
+        .finish();
+    subscriber::with_default(capture, run);
+    let captured = logs.0.lock().unwrap();
+    String::from_utf8_lossy(&captured).into_owned()
+}
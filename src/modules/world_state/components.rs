@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+use bevy::ecs::component::Component;
+use bevy::ecs::prelude::Resource;
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntityState {
+    pub destroyed: bool,
+    pub position: Option<(f32, f32)>,
+    pub values: BTreeMap<String, f32>,
+}
+
+#[derive(Default, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldState {
+    pub entities: BTreeMap<String, EntityState>,
+}
+
+impl WorldState {
+    pub fn get(&self, iid: &str) -> Option<&EntityState> {
+        self.entities.get(iid)
+    }
+
+    pub fn destroy(&mut self, iid: &str) {
+        self.entities.entry(iid.to_string()).or_default().destroyed = true;
+    }
+
+    pub fn set_position(&mut self, iid: &str, position: Vec2) {
+        self.entities.entry(iid.to_string()).or_default().position = Some((position.x, position.y));
+    }
+
+    pub fn value(&self, iid: &str, key: &str) -> Option<f32> {
+        self.entities.get(iid).and_then(|state| state.values.get(key).copied())
+    }
+
+    pub fn set_value(&mut self, iid: &str, key: &str, value: f32) {
+        self.entities.entry(iid.to_string()).or_default().values.insert(key.to_string(), value);
+    }
+}
+
+#[derive(Component)]
+pub struct Persistent {
+    pub spawn_position: Vec2,
+}
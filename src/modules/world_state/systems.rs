@@ -0,0 +1,41 @@
+use bevy::prelude::{Added, Changed, Commands, DespawnRecursiveExt, Entity, Query, Res, ResMut, Transform, Without};
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::prelude::Worldly;
+use crate::modules::world_state::components::{Persistent, WorldState};
+
+#[allow(clippy::type_complexity)]
+pub fn restore_entity_state(
+    mut commands: Commands, world_state: Res<WorldState>,
+    mut spawned: Query<(Entity, &EntityInstance, &mut Transform), (Added<EntityInstance>, Without<Worldly>)>
+) {
+    for (entity, entity_instance, mut transform) in spawned.iter_mut() {
+        let spawn_position = transform.translation.truncate();
+        if let Some(state) = world_state.get(&entity_instance.iid) {
+            if state.destroyed {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+            if let Some((x, y)) = state.position {
+                transform.translation.x = x;
+                transform.translation.y = y;
+            }
+        }
+        commands.entity(entity).insert(Persistent { spawn_position });
+    }
+}
+
+pub fn record_entity_state(
+    mut world_state: ResMut<WorldState>,
+    moved: Query<(&EntityInstance, &Transform, &Persistent), Changed<Transform>>
+) {
+    for (entity_instance, transform, persistent) in moved.iter() {
+        let position = transform.translation.truncate();
+        if position == persistent.spawn_position {
+            continue;
+        }
+        let recorded = world_state.get(&entity_instance.iid).and_then(|state| state.position);
+        if recorded != Some((position.x, position.y)) {
+            world_state.set_position(&entity_instance.iid, position);
+        }
+    }
+}
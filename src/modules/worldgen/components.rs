@@ -0,0 +1,11 @@
+use std::collections::HashSet;
+use bevy::asset::HandleId;
+use bevy::prelude::Resource;
+
+#[derive(Default, Resource)]
+pub struct GeneratedLevels {
+    // levels filled in this frame, so the modified event that follows isn't taken for a reload
+    pub pending: HashSet<HandleId>,
+    // what the levels were filled from, a loaded save with another seed has them filled again
+    pub seed: Option<u64>,
+}
@@ -0,0 +1,17 @@
+use crate::modules::level::consts::LEVEL_GRID_SIZE;
+
+pub const CELL_SIZE: f32 = LEVEL_GRID_SIZE;
+
+pub const MEADOW_TILES: &[usize] = &[0, 1, 16, 17];
+pub const FOREST_TILES: &[usize] = &[2, 3, 18, 19];
+pub const ROCKY_TILES: &[usize] = &[34, 35, 50, 51];
+pub const WATER_TILES: &[usize] = &[32, 33];
+
+pub const BIOME_SCALE: f32 = 12.;
+pub const NOISE_OCTAVES: usize = 3;
+pub const WATER_LEVEL: f32 = 0.3;
+pub const ROCKY_LEVEL: f32 = 0.68;
+pub const FOREST_MOISTURE: f32 = 0.5;
+
+pub const POISSON_RADIUS: f32 = 28.;
+pub const POISSON_ATTEMPTS: usize = 30;
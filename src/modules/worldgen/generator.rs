@@ -0,0 +1,180 @@
+use std::f32::consts::{SQRT_2, TAU};
+use bevy::math::{IVec2, Vec2};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::Deserialize;
+use crate::modules::level::consts::{BUSH_IDENTIFIER, FLOWER_IDENTIFIER, ROCK_IDENTIFIER, TREE_STUMP_IDENTIFIER};
+use crate::modules::worldgen::consts::{BIOME_SCALE, CELL_SIZE, FOREST_MOISTURE, FOREST_TILES, MEADOW_TILES, NOISE_OCTAVES, POISSON_ATTEMPTS, POISSON_RADIUS, ROCKY_LEVEL, ROCKY_TILES, WATER_LEVEL, WATER_TILES};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub enum Biome { MEADOW, FOREST, ROCKY, WATER }
+
+pub struct PropPlacement {
+    pub identifier: &'static str,
+    // pixels from the top left corner of the region, like LDtk's px
+    pub position: Vec2,
+}
+
+pub struct GeneratedRegion {
+    pub width: usize,
+    pub height: usize,
+    pub biomes: Vec<Biome>,
+    pub tiles: Vec<usize>,
+    pub collision: Vec<bool>,
+    pub props: Vec<PropPlacement>,
+}
+
+impl GeneratedRegion {
+    pub fn biome_at(&self, position: Vec2) -> Biome {
+        let x = ((position.x / CELL_SIZE) as usize).min(self.width - 1);
+        let y = ((position.y / CELL_SIZE) as usize).min(self.height - 1);
+        self.biomes[y * self.width + x]
+    }
+}
+
+// biomes are sampled in world cells with the shared noise seed so neighbouring regions line up,
+// everything else only depends on the region seed
+pub fn generate_region(origin: IVec2, width: usize, height: usize, noise_seed: u64, region_seed: u64) -> GeneratedRegion {
+    let mut rng = ChaCha12Rng::seed_from_u64(region_seed);
+    let mut region = GeneratedRegion {
+        width, height, biomes: Vec::with_capacity(width * height), tiles: Vec::with_capacity(width * height),
+        collision: Vec::with_capacity(width * height), props: Vec::new()
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let biome = biome_at(noise_seed, IVec2::new(origin.x + x as i32, origin.y + y as i32));
+            let tiles = match biome {
+                Biome::MEADOW => MEADOW_TILES,
+                Biome::FOREST => FOREST_TILES,
+                Biome::ROCKY => ROCKY_TILES,
+                Biome::WATER => WATER_TILES,
+            };
+            region.tiles.push(tiles[rng.gen_range(0..tiles.len())]);
+            region.collision.push(biome == Biome::WATER);
+            region.biomes.push(biome);
+        }
+    }
+
+    let size = Vec2::new(width as f32, height as f32) * CELL_SIZE;
+    for position in poisson_disk(&mut rng, size, POISSON_RADIUS) {
+        if let Some(identifier) = choose_prop(&mut rng, region.biome_at(position)) {
+            region.props.push(PropPlacement { identifier, position });
+        }
+    }
+    region
+}
+
+// the biome of a world cell, counted like LDtk's world coordinates
+pub fn biome_at(noise_seed: u64, cell: IVec2) -> Biome {
+    biome(noise_seed, cell.as_vec2() / BIOME_SCALE)
+}
+
+fn biome(seed: u64, point: Vec2) -> Biome {
+    let elevation = fractal_noise(seed, point);
+    let moisture = fractal_noise(seed.wrapping_add(1), point);
+    if elevation < WATER_LEVEL {
+        Biome::WATER
+    } else if elevation > ROCKY_LEVEL {
+        Biome::ROCKY
+    } else if moisture > FOREST_MOISTURE {
+        Biome::FOREST
+    } else {
+        Biome::MEADOW
+    }
+}
+
+fn choose_prop(rng: &mut ChaCha12Rng, biome: Biome) -> Option<&'static str> {
+    let roll = rng.gen::<f32>();
+    match biome {
+        Biome::FOREST if roll < 0.45 => Some(TREE_STUMP_IDENTIFIER),
+        Biome::FOREST if roll < 0.75 => Some(BUSH_IDENTIFIER),
+        Biome::ROCKY if roll < 0.5 => Some(ROCK_IDENTIFIER),
+        Biome::ROCKY if roll < 0.6 => Some(TREE_STUMP_IDENTIFIER),
+        Biome::MEADOW if roll < 0.25 => Some(FLOWER_IDENTIFIER),
+        Biome::MEADOW if roll < 0.35 => Some(BUSH_IDENTIFIER),
+        _ => None
+    }
+}
+
+fn lattice_value(seed: u64, x: i32, y: i32) -> f32 {
+    let mut hash = seed ^ (x as u64).wrapping_mul(0x9e3779b97f4a7c15) ^ (y as u64).wrapping_mul(0xc2b2ae3d27d4eb4f);
+    hash = (hash ^ (hash >> 33)).wrapping_mul(0xff51afd7ed558ccd);
+    hash = (hash ^ (hash >> 33)).wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+fn value_noise(seed: u64, point: Vec2) -> f32 {
+    let corner = point.floor();
+    let t = point - corner;
+    let smooth = t * t * (Vec2::splat(3.) - 2. * t);
+    let (x, y) = (corner.x as i32, corner.y as i32);
+    let bottom = lattice_value(seed, x, y) + (lattice_value(seed, x + 1, y) - lattice_value(seed, x, y)) * smooth.x;
+    let top = lattice_value(seed, x, y + 1) + (lattice_value(seed, x + 1, y + 1) - lattice_value(seed, x, y + 1)) * smooth.x;
+    bottom + (top - bottom) * smooth.y
+}
+
+fn fractal_noise(seed: u64, point: Vec2) -> f32 {
+    let mut value = 0.;
+    let mut amplitude = 1.;
+    let mut total_amplitude = 0.;
+    for octave in 0..NOISE_OCTAVES {
+        value += value_noise(seed.wrapping_add(octave as u64 * 7919), point * (1 << octave) as f32) * amplitude;
+        total_amplitude += amplitude;
+        amplitude /= 2.;
+    }
+    value / total_amplitude
+}
+
+// Bridson's algorithm, samples are kept at least `radius` apart
+fn poisson_disk(rng: &mut ChaCha12Rng, size: Vec2, radius: f32) -> Vec<Vec2> {
+    let cell_size = radius / SQRT_2;
+    let columns = (size.x / cell_size).ceil() as usize;
+    let rows = (size.y / cell_size).ceil() as usize;
+    let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+    let mut samples = Vec::new();
+    let mut active = Vec::new();
+    let grid_index = |point: Vec2| ((point.y / cell_size) as usize).min(rows - 1) * columns + ((point.x / cell_size) as usize).min(columns - 1);
+
+    let first = Vec2::new(rng.gen::<f32>() * size.x, rng.gen::<f32>() * size.y);
+    grid[grid_index(first)] = Some(0);
+    samples.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let active_index = rng.gen_range(0..active.len());
+        let origin = samples[active[active_index]];
+        let mut found = false;
+        for _ in 0..POISSON_ATTEMPTS {
+            let angle = rng.gen::<f32>() * TAU;
+            let distance = radius * (1. + rng.gen::<f32>());
+            let candidate = origin + Vec2::new(angle.cos(), angle.sin()) * distance;
+            if candidate.x < 0. || candidate.y < 0. || candidate.x >= size.x || candidate.y >= size.y {
+                continue;
+            }
+            let column = (candidate.x / cell_size) as usize;
+            let row = (candidate.y / cell_size) as usize;
+            let mut far_enough = true;
+            for neighbour_row in row.saturating_sub(2)..(row + 3).min(rows) {
+                for neighbour_column in column.saturating_sub(2)..(column + 3).min(columns) {
+                    if let Some(neighbour) = grid[neighbour_row * columns + neighbour_column] {
+                        if samples[neighbour].distance(candidate) < radius {
+                            far_enough = false;
+                        }
+                    }
+                }
+            }
+            if far_enough {
+                grid[grid_index(candidate)] = Some(samples.len());
+                active.push(samples.len());
+                samples.push(candidate);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            active.swap_remove(active_index);
+        }
+    }
+    samples
+}
@@ -0,0 +1,4 @@
+pub mod components;
+pub mod consts;
+pub mod generator;
+pub mod systems;
@@ -0,0 +1,154 @@
+use bevy::asset::{AssetEvent, Assets, Handle, HandleId};
+use bevy::log::warn;
+use bevy::math::{IVec2, Vec2};
+use bevy::prelude::{EventReader, Res, ResMut};
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::ldtk::{Definitions, Level, TileInstance};
+use bevy_ecs_ldtk::prelude::{LdtkAsset, LdtkLevel};
+use crate::modules::level::consts::{COLLISION_LAYER_IDENTIFIER, ENTITY_LAYER_IDENTIFIER, PROCEDURAL_FIELD, TILE_LAYER_IDENTIFIER};
+use crate::modules::level::fields::{bool_field, default_field_instances};
+use crate::modules::navigation::consts::COLLISION_INT_GRID_VALUE;
+use crate::modules::rng::components::{RngResource, RngStream};
+use crate::modules::worldgen::components::GeneratedLevels;
+use crate::modules::worldgen::consts::CELL_SIZE;
+use crate::modules::worldgen::generator::{generate_region, GeneratedRegion};
+
+// procedural levels are filled in before they spawn, so LDtk spawns what was generated like anything placed by hand
+pub fn generate_procedural_levels(
+    mut asset_events: EventReader<AssetEvent<LdtkLevel>>, mut ldtk_levels: ResMut<Assets<LdtkLevel>>,
+    ldtk_assets: Res<Assets<LdtkAsset>>, rng: Res<RngResource>, mut generated: ResMut<GeneratedLevels>
+) {
+    let mut handles: Vec<HandleId> = asset_events.iter()
+        .filter_map(|asset_event| match asset_event {
+            AssetEvent::Created { handle } => Some(handle.id()),
+            AssetEvent::Modified { handle } if !generated.pending.remove(&handle.id()) => Some(handle.id()),
+            _ => None
+        })
+        .collect();
+    let seed = rng.derive_seed(RngStream::WORLDGEN.name());
+    if generated.seed.replace(seed).is_some_and(|previous| previous != seed) {
+        handles = ldtk_levels.ids().collect();
+    }
+    for handle in handles {
+        let defs = match ldtk_assets.iter().find(|(_, ldtk_asset)| ldtk_asset.level_map.values().any(|level| level.id() == handle)) {
+            Some((_, ldtk_asset)) => &ldtk_asset.project.defs,
+            None => continue
+        };
+        let region = match ldtk_levels.get(&Handle::weak(handle)).map(|ldtk_level| &ldtk_level.level) {
+            Some(level) if bool_field(&level.identifier, &level.field_instances, PROCEDURAL_FIELD) == Some(true) => {
+                let cell = CELL_SIZE as i32;
+                generate_region(
+                    IVec2::new(level.world_x / cell, level.world_y / cell),
+                    (level.px_wid / cell) as usize, (level.px_hei / cell) as usize,
+                    seed,
+                    rng.derive_seed(&format!("{}:{}", RngStream::WORLDGEN.name(), level.iid)),
+                )
+            }
+            _ => continue
+        };
+        if let Some(ldtk_level) = ldtk_levels.get_mut(&Handle::weak(handle)) {
+            fill_level(&mut ldtk_level.level, &region, defs);
+            generated.pending.insert(handle);
+        }
+    }
+}
+
+fn fill_level(level: &mut Level, region: &GeneratedRegion, defs: &Definitions) {
+    let layer_instances = match level.layer_instances.as_mut() {
+        Some(layer_instances) => layer_instances,
+        None => return
+    };
+    for layer_instance in layer_instances.iter_mut() {
+        let layer_width = layer_instance.c_wid as usize;
+        let grid_size = layer_instance.grid_size;
+        match layer_instance.identifier.as_str() {
+            TILE_LAYER_IDENTIFIER => {
+                let tileset = match defs.tilesets.iter().find(|tileset| Some(tileset.uid) == layer_instance.tileset_def_uid) {
+                    Some(tileset) => tileset,
+                    None => continue
+                };
+                layer_instance.grid_tiles = region.tiles.iter().enumerate().map(|(index, tile)| {
+                    let tile = *tile as i32;
+                    let cell = IVec2::new((index % region.width) as i32, (index / region.width) as i32);
+                    let source = IVec2::new(tile % tileset.c_wid, tile / tileset.c_wid);
+                    TileInstance {
+                        px: cell * grid_size,
+                        src: IVec2::splat(tileset.padding) + source * (tileset.tile_grid_size + tileset.spacing),
+                        t: tile,
+                        d: vec![cell.y * layer_width as i32 + cell.x],
+                        f: 0,
+                    }
+                }).collect();
+            }
+            COLLISION_LAYER_IDENTIFIER => {
+                layer_instance.int_grid_csv = region.collision.iter()
+                    .map(|blocked| if *blocked { COLLISION_INT_GRID_VALUE } else { 0 })
+                    .collect();
+            }
+            ENTITY_LAYER_IDENTIFIER => {
+                // props from an earlier fill make way, the ones placed by hand stay
+                let generated_prefix = format!("{}-generated-", level.iid);
+                layer_instance.entity_instances.retain(|entity_instance| !entity_instance.iid.starts_with(&generated_prefix));
+                for (index, placement) in region.props.iter().enumerate() {
+                    let definition = match defs.entities.iter().find(|definition| definition.identifier == placement.identifier) {
+                        Some(definition) => definition,
+                        None => {
+                            warn!("Generated {} has no entity definition in the LDtk project", placement.identifier);
+                            continue;
+                        }
+                    };
+                    let size = IVec2::new(definition.width, definition.height);
+                    let pivot = Vec2::new(definition.pivot_x, definition.pivot_y);
+                    // placements are centers, LDtk places entities by their pivot
+                    let px = (placement.position - (Vec2::splat(0.5) - pivot) * size.as_vec2()).round().as_ivec2();
+                    layer_instance.entity_instances.push(EntityInstance {
+                        grid: px / grid_size,
+                        identifier: definition.identifier.clone(),
+                        pivot,
+                        smart_color: definition.color,
+                        tags: definition.tags.clone(),
+                        tile: definition.tile_rect.clone(),
+                        def_uid: definition.uid,
+                        field_instances: default_field_instances(definition),
+                        width: size.x,
+                        height: size.y,
+                        // stable for a seed, and the seed is saved with the rng state
+                        iid: format!("{}{}", generated_prefix, index),
+                        px,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use bevy_ecs_ldtk::ldtk::LdtkJson;
+    use super::*;
+
+    fn entity_iids(level: &Level) -> Vec<String> {
+        level.layer_instances.iter().flatten()
+            .filter(|layer_instance| layer_instance.identifier == ENTITY_LAYER_IDENTIFIER)
+            .flat_map(|layer_instance| layer_instance.entity_instances.iter().map(|entity_instance| entity_instance.iid.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn filling_again_replaces_the_props_of_the_last_fill() {
+        let project: LdtkJson = serde_json::from_str(&fs::read_to_string("assets/tilemaps/simple-forest.ldtk").unwrap()).unwrap();
+        let mut level = project.levels[0].clone();
+        let placed = entity_iids(&level);
+        let region = |seed| generate_region(IVec2::ZERO, 16, 16, seed, seed);
+        let (first, second) = (region(1), region(2));
+        assert_ne!(first.props.len(), second.props.len());
+
+        fill_level(&mut level, &first, &project.defs);
+        fill_level(&mut level, &second, &project.defs);
+        let iids = entity_iids(&level);
+        assert_eq!(iids.len(), placed.len() + second.props.len());
+        assert!(placed.iter().all(|iid| iids.contains(iid)));
+    }
+}